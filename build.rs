@@ -1,5 +1,14 @@
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tonic_build::compile_protos("proto/project.proto")?;
-    tonic_build::compile_protos("proto/project_status.proto")?;
+    tonic_build::compile_protos("proto/common.proto")?;
+    tonic_build::compile_protos("proto/incident.proto")?;
+    tonic_build::configure()
+        .extern_path(".common", "crate::grpc::common_proto")
+        .compile(
+            &["proto/project.proto", "proto/project_status.proto"],
+            &["proto"],
+        )?;
+    tonic_build::configure()
+        .build_server(false)
+        .compile(&["proto/health.proto"], &["proto"])?;
     Ok(())
 }
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+CREATE TABLE project_recipients (
+    project_recipient_id VARCHAR PRIMARY KEY,
+    email VARCHAR NOT NULL,
+    project_id VARCHAR NOT NULL
+);
+
+ALTER TABLE project_recipients ADD UNIQUE (project_id, email);
+ALTER TABLE project_recipients ADD CONSTRAINT fk_project_id FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE;
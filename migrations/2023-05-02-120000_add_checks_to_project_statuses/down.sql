@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE project_statuses
+    DROP COLUMN check_enabled,
+    DROP COLUMN check_target,
+    DROP COLUMN check_expected_status,
+    DROP COLUMN check_expected_body,
+    DROP COLUMN check_max_latency_ms;
\ No newline at end of file
@@ -0,0 +1,7 @@
+-- Your SQL goes here
+ALTER TABLE project_statuses
+    ADD COLUMN check_enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN check_target VARCHAR,
+    ADD COLUMN check_expected_status INTEGER,
+    ADD COLUMN check_expected_body VARCHAR,
+    ADD COLUMN check_max_latency_ms INTEGER;
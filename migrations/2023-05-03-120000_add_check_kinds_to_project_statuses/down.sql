@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE project_statuses
+    DROP COLUMN check_kind,
+    DROP COLUMN check_interval_secs,
+    DROP COLUMN check_timeout_ms,
+    DROP COLUMN check_grpc_service;
+
+DROP TYPE check_kind;
\ No newline at end of file
@@ -0,0 +1,8 @@
+-- Your SQL goes here
+CREATE TYPE check_kind AS ENUM ('http', 'tcp', 'grpc_health', 'keyword');
+
+ALTER TABLE project_statuses
+    ADD COLUMN check_kind check_kind NOT NULL DEFAULT 'http',
+    ADD COLUMN check_interval_secs INTEGER,
+    ADD COLUMN check_timeout_ms INTEGER,
+    ADD COLUMN check_grpc_service VARCHAR;
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE project_statuses
+    DROP COLUMN failure_threshold,
+    DROP COLUMN success_threshold,
+    DROP COLUMN pending_reports,
+    DROP COLUMN flapping,
+    DROP COLUMN flap_transitions,
+    DROP COLUMN flap_window_started_at;
\ No newline at end of file
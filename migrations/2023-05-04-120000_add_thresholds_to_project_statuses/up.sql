@@ -0,0 +1,8 @@
+-- Your SQL goes here
+ALTER TABLE project_statuses
+    ADD COLUMN failure_threshold INTEGER NOT NULL DEFAULT 1,
+    ADD COLUMN success_threshold INTEGER NOT NULL DEFAULT 1,
+    ADD COLUMN pending_reports INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN flapping BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN flap_transitions INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN flap_window_started_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP;
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE project_statuses DROP COLUMN health_state;
+
+DROP TYPE health_state;
\ No newline at end of file
@@ -0,0 +1,14 @@
+-- Your SQL goes here
+CREATE TYPE health_state AS ENUM (
+    'operational',
+    'degraded',
+    'partial_outage',
+    'major_outage',
+    'maintenance',
+    'unknown'
+);
+
+ALTER TABLE project_statuses ADD COLUMN health_state health_state NOT NULL DEFAULT 'unknown';
+
+UPDATE project_statuses
+SET health_state = CASE WHEN is_healthy THEN 'operational'::health_state ELSE 'major_outage'::health_state END;
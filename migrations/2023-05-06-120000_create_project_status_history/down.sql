@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE project_status_history;
+
+DROP TYPE report_source;
\ No newline at end of file
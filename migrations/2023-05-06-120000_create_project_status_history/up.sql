@@ -0,0 +1,15 @@
+-- Your SQL goes here
+CREATE TYPE report_source AS ENUM ('rest', 'grpc', 'prober');
+
+CREATE TABLE project_status_history (
+    project_status_history_id VARCHAR PRIMARY KEY,
+    project_status_id VARCHAR NOT NULL,
+    old_state health_state NOT NULL,
+    new_state health_state NOT NULL,
+    source report_source NOT NULL,
+    reporter VARCHAR,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX project_status_history_created_at_idx ON project_status_history (project_status_id, created_at);
+ALTER TABLE project_status_history ADD CONSTRAINT fk_project_status_id FOREIGN KEY (project_status_id) REFERENCES project_statuses(project_status_id) ON DELETE CASCADE;
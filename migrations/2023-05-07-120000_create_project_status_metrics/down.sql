@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE project_status_metrics;
\ No newline at end of file
@@ -0,0 +1,11 @@
+-- Your SQL goes here
+CREATE TABLE project_status_metrics (
+    project_status_metric_id VARCHAR PRIMARY KEY,
+    project_status_id VARCHAR NOT NULL,
+    latency_ms INTEGER,
+    detail VARCHAR,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX project_status_metrics_created_at_idx ON project_status_metrics (project_status_id, created_at);
+ALTER TABLE project_status_metrics ADD CONSTRAINT fk_project_status_id FOREIGN KEY (project_status_id) REFERENCES project_statuses(project_status_id) ON DELETE CASCADE;
@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE incident_project_statuses;
+DROP TABLE incident_timeline;
+DROP TABLE incidents;
+
+DROP TYPE incident_state;
+DROP TYPE incident_severity;
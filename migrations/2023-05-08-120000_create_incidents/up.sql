@@ -0,0 +1,37 @@
+-- Your SQL goes here
+CREATE TYPE incident_severity AS ENUM ('minor', 'major', 'critical');
+CREATE TYPE incident_state AS ENUM ('investigating', 'identified', 'monitoring', 'resolved');
+
+CREATE TABLE incidents (
+    incident_id VARCHAR PRIMARY KEY,
+    project_id VARCHAR NOT NULL,
+    title VARCHAR NOT NULL,
+    severity incident_severity NOT NULL,
+    state incident_state NOT NULL DEFAULT 'investigating',
+    automatic BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TIMESTAMPTZ
+);
+
+CREATE INDEX incidents_created_at_idx ON incidents (project_id, created_at);
+ALTER TABLE incidents ADD CONSTRAINT fk_project_id FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE;
+
+CREATE TABLE incident_timeline (
+    incident_timeline_id VARCHAR PRIMARY KEY,
+    incident_id VARCHAR NOT NULL,
+    state incident_state NOT NULL,
+    message VARCHAR NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX incident_timeline_created_at_idx ON incident_timeline (incident_id, created_at);
+ALTER TABLE incident_timeline ADD CONSTRAINT fk_incident_id FOREIGN KEY (incident_id) REFERENCES incidents(incident_id) ON DELETE CASCADE;
+
+CREATE TABLE incident_project_statuses (
+    incident_id VARCHAR NOT NULL,
+    project_status_id VARCHAR NOT NULL,
+    PRIMARY KEY (incident_id, project_status_id)
+);
+
+ALTER TABLE incident_project_statuses ADD CONSTRAINT fk_incident_id FOREIGN KEY (incident_id) REFERENCES incidents(incident_id) ON DELETE CASCADE;
+ALTER TABLE incident_project_statuses ADD CONSTRAINT fk_project_status_id FOREIGN KEY (project_status_id) REFERENCES project_statuses(project_status_id) ON DELETE CASCADE;
@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE maintenance_window_project_statuses;
+DROP TABLE maintenance_windows;
+
+DELETE FROM project_status_history WHERE source = 'maintenance';
+ALTER TYPE report_source RENAME TO report_source_old;
+CREATE TYPE report_source AS ENUM ('rest', 'grpc', 'prober');
+ALTER TABLE project_status_history ALTER COLUMN source TYPE report_source USING source::text::report_source;
+DROP TYPE report_source_old;
@@ -0,0 +1,24 @@
+-- Your SQL goes here
+ALTER TYPE report_source ADD VALUE 'maintenance';
+
+CREATE TABLE maintenance_windows (
+    maintenance_window_id VARCHAR PRIMARY KEY,
+    project_id VARCHAR NOT NULL,
+    message VARCHAR NOT NULL,
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (ends_at > starts_at)
+);
+
+CREATE INDEX maintenance_windows_period_idx ON maintenance_windows (starts_at, ends_at);
+ALTER TABLE maintenance_windows ADD CONSTRAINT fk_project_id FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE;
+
+CREATE TABLE maintenance_window_project_statuses (
+    maintenance_window_id VARCHAR NOT NULL,
+    project_status_id VARCHAR NOT NULL,
+    PRIMARY KEY (maintenance_window_id, project_status_id)
+);
+
+ALTER TABLE maintenance_window_project_statuses ADD CONSTRAINT fk_maintenance_window_id FOREIGN KEY (maintenance_window_id) REFERENCES maintenance_windows(maintenance_window_id) ON DELETE CASCADE;
+ALTER TABLE maintenance_window_project_statuses ADD CONSTRAINT fk_project_status_id FOREIGN KEY (project_status_id) REFERENCES project_statuses(project_status_id) ON DELETE CASCADE;
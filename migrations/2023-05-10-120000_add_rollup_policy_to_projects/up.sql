@@ -0,0 +1,4 @@
+-- Your SQL goes here
+CREATE TYPE rollup_policy AS ENUM ('worst', 'majority', 'best');
+
+ALTER TABLE projects ADD COLUMN rollup_policy rollup_policy NOT NULL DEFAULT 'worst';
@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER set_updated_at ON project_statuses;
+DROP TRIGGER set_updated_at ON projects;
+
+ALTER TABLE project_statuses DROP COLUMN updated_at, DROP COLUMN created_at;
+
+ALTER TABLE projects DROP COLUMN updated_at, DROP COLUMN created_at;
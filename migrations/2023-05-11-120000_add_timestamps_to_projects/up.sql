@@ -0,0 +1,11 @@
+-- Your SQL goes here
+ALTER TABLE projects
+    ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT now();
+
+ALTER TABLE project_statuses
+    ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT now();
+
+SELECT diesel_manage_updated_at('projects');
+SELECT diesel_manage_updated_at('project_statuses');
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX projects_search_vector_idx;
+
+ALTER TABLE projects DROP COLUMN search_vector;
@@ -0,0 +1,8 @@
+-- Your SQL goes here
+ALTER TABLE projects ADD COLUMN search_vector TSVECTOR GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', coalesce(name, '')), 'A') ||
+    setweight(to_tsvector('english', coalesce(description, '')), 'B') ||
+    setweight(to_tsvector('english', coalesce(github_repository, '')), 'C')
+) STORED;
+
+CREATE INDEX projects_search_vector_idx ON projects USING GIN (search_vector);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE project_tags;
+
+DROP TABLE tags;
@@ -0,0 +1,15 @@
+-- Your SQL goes here
+CREATE TABLE tags (
+    tag_id VARCHAR PRIMARY KEY,
+    name VARCHAR NOT NULL UNIQUE
+);
+
+CREATE TABLE project_tags (
+    project_id VARCHAR NOT NULL,
+    tag_id VARCHAR NOT NULL,
+    PRIMARY KEY (project_id, tag_id)
+);
+
+CREATE INDEX project_tags_tag_id_idx ON project_tags (tag_id);
+ALTER TABLE project_tags ADD CONSTRAINT fk_project_id FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE;
+ALTER TABLE project_tags ADD CONSTRAINT fk_tag_id FOREIGN KEY (tag_id) REFERENCES tags(tag_id) ON DELETE CASCADE;
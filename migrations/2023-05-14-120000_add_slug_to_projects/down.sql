@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE project_slug_redirects;
+
+ALTER TABLE projects DROP COLUMN slug;
@@ -0,0 +1,27 @@
+-- Your SQL goes here
+ALTER TABLE projects ADD COLUMN slug VARCHAR;
+
+-- Same rules as `Project::slugify`, numbering the projects whose names collide.
+UPDATE projects SET slug = numbered.slug FROM (
+    SELECT project_id, CASE WHEN n = 1 THEN base ELSE base || '-' || n END AS slug
+    FROM (
+        SELECT project_id, base, row_number() OVER (PARTITION BY base ORDER BY project_id) AS n
+        FROM (
+            SELECT project_id,
+                coalesce(nullif(trim(BOTH '-' FROM regexp_replace(lower(name), '[^a-z0-9]+', '-', 'g')), ''), 'project') AS base
+            FROM projects
+        ) bases
+    ) ranked
+) numbered
+WHERE projects.project_id = numbered.project_id;
+
+ALTER TABLE projects ALTER COLUMN slug SET NOT NULL;
+ALTER TABLE projects ADD CONSTRAINT projects_slug_key UNIQUE (slug);
+
+CREATE TABLE project_slug_redirects (
+    slug VARCHAR PRIMARY KEY,
+    project_id VARCHAR NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE project_slug_redirects ADD CONSTRAINT fk_project_id FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE;
@@ -0,0 +1,21 @@
+-- Your SQL goes here
+CREATE TYPE media_kind AS ENUM ('screenshot', 'cover');
+
+CREATE TABLE project_media (
+    project_media_id VARCHAR PRIMARY KEY,
+    project_id VARCHAR NOT NULL,
+    kind media_kind NOT NULL DEFAULT 'screenshot',
+    content_type VARCHAR NOT NULL,
+    byte_size INTEGER NOT NULL,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    caption VARCHAR,
+    url VARCHAR NOT NULL,
+    thumbnail_url VARCHAR NOT NULL,
+    storage_key VARCHAR NOT NULL,
+    thumbnail_key VARCHAR NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX project_media_created_at_idx ON project_media (project_id, created_at);
+ALTER TABLE project_media ADD CONSTRAINT fk_project_id FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE;
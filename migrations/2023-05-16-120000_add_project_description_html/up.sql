@@ -0,0 +1,7 @@
+-- Your SQL goes here
+ALTER TABLE projects ADD COLUMN description_html VARCHAR;
+
+-- Existing descriptions were plain text, so they become a single escaped paragraph.
+UPDATE projects
+SET description_html = '<p>' || replace(replace(replace(description, '&', '&amp;'), '<', '&lt;'), '>', '&gt;') || '</p>'
+WHERE description IS NOT NULL AND description <> '';
@@ -0,0 +1,14 @@
+-- Your SQL goes here
+CREATE TYPE link_kind AS ENUM ('homepage', 'repository', 'docs', 'demo', 'crates_io', 'npm', 'pypi', 'other');
+
+CREATE TABLE project_links (
+    project_link_id VARCHAR PRIMARY KEY,
+    project_id VARCHAR NOT NULL,
+    kind link_kind NOT NULL DEFAULT 'other',
+    url VARCHAR NOT NULL,
+    label VARCHAR,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (project_id, url)
+);
+
+ALTER TABLE project_links ADD CONSTRAINT fk_project_id FOREIGN KEY (project_id) REFERENCES projects(project_id) ON DELETE CASCADE;
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX projects_position_idx;
+
+ALTER TABLE projects DROP COLUMN position;
+ALTER TABLE projects DROP COLUMN featured;
@@ -0,0 +1,12 @@
+-- Your SQL goes here
+ALTER TABLE projects ADD COLUMN featured BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE projects ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+
+-- Existing projects keep the order they were listed in so far, oldest first.
+UPDATE projects SET position = ordered.position FROM (
+    SELECT project_id, row_number() OVER (ORDER BY created_at, project_id) - 1 AS position
+    FROM projects
+) ordered
+WHERE projects.project_id = ordered.project_id;
+
+CREATE INDEX projects_position_idx ON projects (position, project_id);
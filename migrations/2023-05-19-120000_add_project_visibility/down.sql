@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE projects DROP COLUMN visibility;
+
+DROP TYPE visibility;
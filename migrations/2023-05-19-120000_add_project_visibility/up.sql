@@ -0,0 +1,5 @@
+-- Your SQL goes here
+CREATE TYPE visibility AS ENUM ('draft', 'unlisted', 'published', 'archived');
+
+-- Every existing project was public so far.
+ALTER TABLE projects ADD COLUMN visibility visibility NOT NULL DEFAULT 'published';
@@ -0,0 +1,14 @@
+-- This file should undo anything in `up.sql`
+DELETE FROM project_statuses
+WHERE deleted_at IS NOT NULL
+    OR project_id IN (SELECT project_id FROM projects WHERE deleted_at IS NOT NULL);
+DELETE FROM projects WHERE deleted_at IS NOT NULL;
+
+DROP INDEX projects_name_key;
+ALTER TABLE projects ADD CONSTRAINT projects_name_key UNIQUE (name);
+
+DROP INDEX project_statuses_deleted_at_idx;
+DROP INDEX projects_deleted_at_idx;
+
+ALTER TABLE project_statuses DROP COLUMN deleted_at;
+ALTER TABLE projects DROP COLUMN deleted_at;
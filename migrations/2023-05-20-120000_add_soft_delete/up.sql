@@ -0,0 +1,10 @@
+-- Your SQL goes here
+ALTER TABLE projects ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE project_statuses ADD COLUMN deleted_at TIMESTAMPTZ;
+
+CREATE INDEX projects_deleted_at_idx ON projects (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX project_statuses_deleted_at_idx ON project_statuses (deleted_at) WHERE deleted_at IS NOT NULL;
+
+-- Deleted projects give their name up until they are restored.
+ALTER TABLE projects DROP CONSTRAINT projects_name_key;
+CREATE UNIQUE INDEX projects_name_key ON projects (name) WHERE deleted_at IS NULL;
@@ -0,0 +1,76 @@
+use rocket::http::Header;
+
+use crate::escape;
+
+/// How long clients and proxies may cache a badge, in seconds.
+const MAX_AGE_SECS: u32 = 300;
+
+#[derive(FromFormField, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BadgeStyle {
+    #[default]
+    Flat,
+    #[field(value = "flat-square")]
+    FlatSquare,
+}
+
+/// A shields-style SVG badge served with cache headers.
+#[derive(Responder)]
+#[response(content_type = "image/svg+xml")]
+pub struct Badge {
+    svg: String,
+    cache_control: Header<'static>,
+}
+
+impl Badge {
+    pub fn new(label: &str, message: &str, color: &str, style: BadgeStyle) -> Self {
+        Self {
+            svg: render(label, message, color, style),
+            cache_control: Header::new(
+                "Cache-Control",
+                format!("public, max-age={0}, s-maxage={0}", MAX_AGE_SECS),
+            ),
+        }
+    }
+}
+
+fn render(label: &str, message: &str, color: &str, style: BadgeStyle) -> String {
+    let label_width = text_width(label) + 10;
+    let message_width = text_width(message) + 10;
+    let width = label_width + message_width;
+    let (radius, gradient) = match style {
+        BadgeStyle::Flat => (
+            3,
+            "<linearGradient id=\"s\" x2=\"0\" y2=\"100%\"><stop offset=\"0\" stop-color=\"#bbb\" stop-opacity=\".1\"/><stop offset=\"1\" stop-opacity=\".1\"/></linearGradient>",
+        ),
+        BadgeStyle::FlatSquare => (0, ""),
+    };
+    let overlay = if gradient.is_empty() {
+        String::new()
+    } else {
+        format!("<rect width=\"{}\" height=\"20\" fill=\"url(#s)\"/>", width)
+    };
+    let (label, message) = (escape::markup(label), escape::markup(message));
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"20\" role=\"img\" aria-label=\"{label}: {message}\">\
+         <title>{label}: {message}</title>{gradient}\
+         <clipPath id=\"r\"><rect width=\"{width}\" height=\"20\" rx=\"{radius}\" fill=\"#fff\"/></clipPath>\
+         <g clip-path=\"url(#r)\"><rect width=\"{label_width}\" height=\"20\" fill=\"#555\"/>\
+         <rect x=\"{label_width}\" width=\"{message_width}\" height=\"20\" fill=\"{color}\"/>{overlay}</g>\
+         <g fill=\"#fff\" text-anchor=\"middle\" font-family=\"Verdana,Geneva,DejaVu Sans,sans-serif\" font-size=\"11\">\
+         <text x=\"{label_x}\" y=\"14\">{label}</text><text x=\"{message_x}\" y=\"14\">{message}</text></g></svg>",
+        label_x = label_width / 2,
+        message_x = label_width + message_width / 2,
+    )
+}
+
+/// Rough width of `text` in 11px Verdana, which is all a badge needs.
+fn text_width(text: &str) -> u32 {
+    text.chars()
+        .map(|c| match c {
+            'i' | 'l' | 'j' | '.' | ',' | ':' | '\'' | '|' | '!' => 4,
+            'm' | 'w' | 'M' | 'W' | '%' => 10,
+            c if c.is_uppercase() => 8,
+            _ => 7,
+        })
+        .sum()
+}
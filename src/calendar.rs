@@ -0,0 +1,143 @@
+use chrono::{DateTime, Utc};
+
+use crate::{
+    escape,
+    models::{maintenance_window::MaintenanceWindow, project::Project},
+};
+
+/// Renders the maintenance windows of `project` as an iCalendar (RFC 5545) feed.
+pub fn maintenance_calendar(
+    project: &Project,
+    maintenance_windows: &[MaintenanceWindow],
+    now: DateTime<Utc>,
+) -> String {
+    let mut lines = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        String::from("PRODID:-//portfolio//maintenance//EN"),
+        String::from("CALSCALE:GREGORIAN"),
+        format!(
+            "X-WR-CALNAME:{}",
+            escape::ical(&format!("{} maintenance", project.name))
+        ),
+    ];
+    for maintenance_window in maintenance_windows {
+        lines.push(String::from("BEGIN:VEVENT"));
+        lines.push(format!(
+            "UID:{}@portfolio",
+            maintenance_window.maintenance_window_id
+        ));
+        lines.push(format!("DTSTAMP:{}", timestamp(now)));
+        lines.push(format!(
+            "DTSTART:{}",
+            timestamp(maintenance_window.starts_at)
+        ));
+        lines.push(format!("DTEND:{}", timestamp(maintenance_window.ends_at)));
+        lines.push(format!(
+            "SUMMARY:{}",
+            escape::ical(&format!("{} maintenance", project.name))
+        ));
+        lines.push(format!(
+            "DESCRIPTION:{}",
+            escape::ical(&maintenance_window.message)
+        ));
+        lines.push(format!("URL:{}", project.url));
+        lines.push(String::from("END:VEVENT"));
+    }
+    lines.push(String::from("END:VCALENDAR"));
+
+    lines.iter().map(|line| fold(line)).collect::<String>()
+}
+
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Terminates the content line with CRLF, folding it so no line is longer than 75
+/// octets without splitting a character.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 8);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::models::{rollup::RollupPolicy, visibility::Visibility};
+
+    fn project(name: &str) -> Project {
+        let now = Utc.with_ymd_and_hms(2023, 5, 1, 12, 0, 0).unwrap();
+        Project {
+            project_id: String::from("project"),
+            name: name.to_string(),
+            description: None,
+            url: String::from("https://example.com"),
+            github_repository: String::from("https://github.com/example/project"),
+            rollup_policy: RollupPolicy::Worst,
+            created_at: now,
+            updated_at: now,
+            slug: Project::slugify(name),
+            description_html: None,
+            featured: false,
+            position: 0,
+            visibility: Visibility::Published,
+            deleted_at: None,
+        }
+    }
+
+    fn maintenance_window(message: &str) -> MaintenanceWindow {
+        let starts_at = Utc.with_ymd_and_hms(2023, 5, 2, 22, 0, 0).unwrap();
+        MaintenanceWindow {
+            maintenance_window_id: String::from("window"),
+            project_id: String::from("project"),
+            message: message.to_string(),
+            starts_at,
+            ends_at: starts_at + chrono::Duration::hours(2),
+            created_at: starts_at,
+        }
+    }
+
+    #[test]
+    fn text_values_are_escaped() {
+        let calendar = maintenance_calendar(
+            &project("Tom, Jerry; co"),
+            &[maintenance_window(
+                "Upgrading the database.\nExpect downtime.",
+            )],
+            Utc::now(),
+        );
+        assert!(calendar.contains("X-WR-CALNAME:Tom\\, Jerry\\; co maintenance\r\n"));
+        assert!(calendar.contains("SUMMARY:Tom\\, Jerry\\; co maintenance\r\n"));
+        assert!(calendar.contains("DESCRIPTION:Upgrading the database.\\nExpect downtime.\r\n"));
+        assert!(calendar.contains("DTSTART:20230502T220000Z\r\n"));
+        assert!(calendar.contains("DTEND:20230503T000000Z\r\n"));
+    }
+
+    #[test]
+    fn long_lines_are_folded() {
+        let message = "é".repeat(100);
+        let calendar = maintenance_calendar(
+            &project("crate"),
+            &[maintenance_window(&message)],
+            Utc::now(),
+        );
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        for line in calendar.split("\r\n") {
+            assert!(line.len() <= 75, "{:?} is longer than 75 octets", line);
+        }
+        let unfolded = calendar.replace("\r\n ", "");
+        assert!(unfolded.contains(&format!("DESCRIPTION:{}\r\n", message)));
+    }
+}
@@ -1,10 +1,23 @@
 use rocket::fairing::{AdHoc, Fairing};
-use std::env;
+use std::{env, time::Duration};
 
 use crate::jwt::JwtService;
+use crate::media::MediaSettings;
+use crate::notifier::{self, Notifier, SmtpSettings, Template};
+use crate::prober::ProberSettings;
 
 pub struct AppConfig {
     pub jwt_service: JwtService,
+    pub notifier: Notifier,
+    pub prober: ProberSettings,
+    /// Base URL links in feeds and media point to, without a trailing slash.
+    pub public_url: String,
+    pub media: MediaSettings,
+    /// How long deleted projects and statuses stay in the trash. They are kept until
+    /// restored when unset.
+    pub trash_retention: Option<Duration>,
+    /// How long latency and detail samples are kept.
+    pub metrics_retention: Duration,
 }
 
 impl AppConfig {
@@ -16,10 +29,48 @@ impl AppConfig {
             );
             let jwt_service = JwtService::new(jwt_secret);
 
-            rocket.manage(AppConfig { jwt_service })
+            let notifier = Notifier::new(Self::get_smtp_settings())
+                .unwrap_or_else(|e| panic!("invalid SMTP configuration: {e}"));
+
+            let prober = ProberSettings {
+                interval: Duration::from_secs(Self::get_env_as_u64("PROBE_INTERVAL_SECS", 60)),
+                timeout: Duration::from_millis(Self::get_env_as_u64("PROBE_TIMEOUT_MS", 5000)),
+            };
+
+            let public_url = Self::get_env_or("PUBLIC_URL", "http://localhost:8000")
+                .trim_end_matches('/')
+                .to_string();
+
+            let media = MediaSettings {
+                dir: Self::get_env_or("MEDIA_DIR", "media").into(),
+                max_bytes: Self::media_max_bytes(),
+            };
+
+            let trash_retention = env::var("TRASH_RETENTION_DAYS").ok().map(|_| {
+                Duration::from_secs(Self::get_env_as_u64("TRASH_RETENTION_DAYS", 0) * 24 * 60 * 60)
+            });
+
+            let metrics_retention = Duration::from_secs(
+                Self::get_env_as_u64("METRICS_RETENTION_DAYS", 90) * 24 * 60 * 60,
+            );
+
+            rocket.manage(AppConfig {
+                jwt_service,
+                notifier,
+                prober,
+                public_url,
+                media,
+                trash_retention,
+                metrics_retention,
+            })
         })
     }
 
+    /// Read before Rocket is built too, to size its upload limits.
+    pub fn media_max_bytes() -> u64 {
+        Self::get_env_as_u64("MEDIA_MAX_BYTES", 10 * 1024 * 1024)
+    }
+
     fn get_env_or_default(key: &str, default_value: &str) -> String {
         env::var(key).unwrap_or_else(|_| {
             if cfg!(debug_assertions) {
@@ -29,4 +80,50 @@ impl AppConfig {
             }
         })
     }
+
+    fn get_env_or(key: &str, default_value: &str) -> String {
+        env::var(key).unwrap_or_else(|_| String::from(default_value))
+    }
+
+    fn get_env_as_u64(key: &str, default_value: u64) -> u64 {
+        env::var(key).map_or(default_value, |value| {
+            value
+                .parse()
+                .unwrap_or_else(|_| panic!("env variable `{key}` should be a number"))
+        })
+    }
+
+    /// Email notifications are only enabled when `SMTP_HOST` is set.
+    fn get_smtp_settings() -> Option<SmtpSettings> {
+        let host = env::var("SMTP_HOST").ok()?;
+        let port = Self::get_env_or("SMTP_PORT", "25")
+            .parse::<u16>()
+            .expect("env variable `SMTP_PORT` should be a valid port");
+        let credentials = match (env::var("SMTP_USERNAME"), env::var("SMTP_PASSWORD")) {
+            (Ok(username), Ok(password)) => Some((username, password)),
+            _ => None,
+        };
+
+        Some(SmtpSettings {
+            host,
+            port,
+            starttls: Self::get_env_or("SMTP_STARTTLS", "false") == "true",
+            credentials,
+            from: Self::get_env_or("SMTP_FROM", "portfolio@localhost"),
+            unhealthy: Template::new(
+                Self::get_env_or(
+                    "SMTP_UNHEALTHY_SUBJECT",
+                    notifier::DEFAULT_UNHEALTHY_SUBJECT,
+                ),
+                Self::get_env_or("SMTP_UNHEALTHY_BODY", notifier::DEFAULT_UNHEALTHY_BODY),
+            ),
+            recovered: Template::new(
+                Self::get_env_or(
+                    "SMTP_RECOVERED_SUBJECT",
+                    notifier::DEFAULT_RECOVERED_SUBJECT,
+                ),
+                Self::get_env_or("SMTP_RECOVERED_BODY", notifier::DEFAULT_RECOVERED_BODY),
+            ),
+        })
+    }
 }
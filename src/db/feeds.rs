@@ -0,0 +1,91 @@
+use diesel::prelude::*;
+use diesel::PgConnection;
+
+use crate::schema::{
+    incident_timeline, incidents, project_status_history, project_statuses, projects,
+};
+use crate::{
+    errors::DbError,
+    models::{
+        feed::FeedEntry, incident::Incident, incident::IncidentTimelineEntry,
+        project_status_history::ProjectStatusHistory, visibility::Visibility,
+    },
+};
+
+/// Number of entries in a feed.
+pub const FEED_LENGTH: i64 = 50;
+
+/// The latest status transitions and incident updates, of one project if `pid` is
+/// given or of every published project otherwise, newest first.
+pub fn find_entries<'a>(
+    conn: &mut PgConnection,
+    pid: Option<&str>,
+) -> Result<Vec<FeedEntry>, DbError<'a>> {
+    let mut transitions = project_status_history::table
+        .inner_join(project_statuses::table.inner_join(projects::table))
+        .select((
+            project_status_history::all_columns,
+            project_statuses::name,
+            projects::name,
+        ))
+        .filter(project_statuses::deleted_at.is_null())
+        .filter(projects::deleted_at.is_null())
+        .order(project_status_history::created_at.desc())
+        .limit(FEED_LENGTH)
+        .into_boxed();
+    let mut updates = incident_timeline::table
+        .inner_join(incidents::table.inner_join(projects::table))
+        .select((
+            incident_timeline::all_columns,
+            incidents::all_columns,
+            projects::name,
+        ))
+        .filter(projects::deleted_at.is_null())
+        .order(incident_timeline::created_at.desc())
+        .limit(FEED_LENGTH)
+        .into_boxed();
+    match pid {
+        Some(pid) => {
+            transitions = transitions.filter(projects::project_id.eq(pid.to_string()));
+            updates = updates.filter(projects::project_id.eq(pid.to_string()));
+        }
+        None => {
+            transitions = transitions.filter(projects::visibility.eq(Visibility::Published));
+            updates = updates.filter(projects::visibility.eq(Visibility::Published));
+        }
+    }
+
+    let transitions = transitions.get_results::<(ProjectStatusHistory, String, String)>(conn)?;
+    let updates = updates.get_results::<(IncidentTimelineEntry, Incident, String)>(conn)?;
+
+    let mut entries = transitions
+        .into_iter()
+        .map(|(history, status_name, project_name)| FeedEntry {
+            id: history.project_status_history_id,
+            title: format!("{}: {}", status_name, history.new_state.label()),
+            summary: format!(
+                "{} of {} went from {} to {}.",
+                status_name,
+                project_name,
+                history.old_state.label().to_lowercase(),
+                history.new_state.label().to_lowercase()
+            ),
+            project_name,
+            published: history.created_at,
+        })
+        .chain(
+            updates
+                .into_iter()
+                .map(|(entry, incident, project_name)| FeedEntry {
+                    id: entry.incident_timeline_id,
+                    title: format!("{} ({})", incident.title, entry.state.label()),
+                    summary: entry.message,
+                    project_name,
+                    published: entry.created_at,
+                }),
+        )
+        .collect::<Vec<FeedEntry>>();
+    entries.sort_by(|a, b| b.published.cmp(&a.published));
+    entries.truncate(FEED_LENGTH as usize);
+    Ok(entries)
+}
@@ -0,0 +1,277 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::PgConnection;
+use std::borrow::Cow;
+
+use crate::schema::{
+    incident_project_statuses, incident_timeline,
+    incidents::{all_columns, dsl::*},
+    project_statuses, projects,
+};
+use crate::{
+    errors::DbError,
+    models::{
+        health_state::HealthState,
+        incident::{
+            Incident, IncidentDetail, IncidentProjectStatus, IncidentState, IncidentTimelineEntry,
+        },
+        project_status::ProjectStatus,
+        visibility::Visibility,
+    },
+};
+
+/// Every incident, newest first. `published_only` leaves out the incidents of projects
+/// that aren't published.
+pub fn find<'a>(
+    conn: &mut PgConnection,
+    published_only: bool,
+) -> Result<Vec<Incident>, DbError<'a>> {
+    let mut query = incidents
+        .inner_join(projects::table)
+        .filter(projects::deleted_at.is_null())
+        .select(all_columns)
+        .order(created_at.desc())
+        .into_boxed();
+    if published_only {
+        query = query.filter(projects::visibility.eq(Visibility::Published));
+    }
+    let results = query.get_results::<Incident>(conn)?;
+    Ok(results)
+}
+
+pub fn find_by_id<'a>(conn: &mut PgConnection, id: &str) -> Result<Option<Incident>, DbError<'a>> {
+    let incident = incidents
+        .inner_join(projects::table)
+        .filter(incident_id.eq(id))
+        .filter(projects::deleted_at.is_null())
+        .select(all_columns)
+        .first::<Incident>(conn)
+        .optional()?;
+    Ok(incident)
+}
+
+pub fn find_by_project<'a>(
+    conn: &mut PgConnection,
+    pid: &str,
+) -> Result<Vec<Incident>, DbError<'a>> {
+    let results = incidents
+        .inner_join(projects::table)
+        .filter(project_id.eq(pid))
+        .filter(projects::deleted_at.is_null())
+        .select(all_columns)
+        .order(created_at.desc())
+        .get_results::<Incident>(conn)?;
+    Ok(results)
+}
+
+/// Incidents of the project that are unresolved or were opened since `since`, newest
+/// first.
+pub fn find_recent_by_project<'a>(
+    conn: &mut PgConnection,
+    pid: &str,
+    since: DateTime<Utc>,
+) -> Result<Vec<Incident>, DbError<'a>> {
+    let results = incidents
+        .inner_join(projects::table)
+        .filter(project_id.eq(pid))
+        .filter(projects::deleted_at.is_null())
+        .filter(created_at.ge(since).or(state.ne(IncidentState::Resolved)))
+        .select(all_columns)
+        .order(created_at.desc())
+        .get_results::<Incident>(conn)?;
+    Ok(results)
+}
+
+pub fn find_detail<'a>(
+    conn: &mut PgConnection,
+    id: &str,
+) -> Result<Option<IncidentDetail>, DbError<'a>> {
+    match find_by_id(conn, id)? {
+        Some(incident) => Ok(Some(detail(conn, incident)?)),
+        None => Ok(None),
+    }
+}
+
+/// Creates the incident with its first timeline entry and affected statuses.
+pub fn create<'a>(
+    conn: &mut PgConnection,
+    new_incident: Incident,
+    entry: IncidentTimelineEntry,
+    psids: &[String],
+) -> Result<IncidentDetail, DbError<'a>> {
+    conn.transaction::<_, DbError<'a>, _>(|conn| {
+        let incident = diesel::insert_into(incidents)
+            .values(new_incident)
+            .get_result::<Incident>(conn)?;
+        diesel::insert_into(incident_timeline::table)
+            .values(entry)
+            .execute(conn)?;
+        link(conn, &incident, psids)?;
+        detail(conn, incident)
+    })
+}
+
+/// Updates the incident, adding `entry` to its timeline and replacing its affected
+/// statuses with `psids` if given.
+pub fn update<'a>(
+    conn: &mut PgConnection,
+    id: &str,
+    updated_incident: Incident,
+    entry: Option<IncidentTimelineEntry>,
+    psids: Option<&[String]>,
+) -> Result<IncidentDetail, DbError<'a>> {
+    conn.transaction::<_, DbError<'a>, _>(|conn| {
+        let incident = diesel::update(incidents.filter(incident_id.eq(id)))
+            .set(updated_incident)
+            .get_result::<Incident>(conn)?;
+        if let Some(entry) = entry {
+            diesel::insert_into(incident_timeline::table)
+                .values(entry)
+                .execute(conn)?;
+        }
+        if let Some(psids) = psids {
+            diesel::delete(
+                incident_project_statuses::table
+                    .filter(incident_project_statuses::incident_id.eq(id)),
+            )
+            .execute(conn)?;
+            link(conn, &incident, psids)?;
+        }
+        detail(conn, incident)
+    })
+}
+
+pub fn delete<'a>(conn: &mut PgConnection, id: &str) -> Result<(), DbError<'a>> {
+    diesel::delete(incidents.filter(incident_id.eq(id))).execute(conn)?;
+    Ok(())
+}
+
+/// Opens an automatic incident for `project_status` unless an unresolved one
+/// already covers it. The status row stays locked until the incident is created, so
+/// concurrent reports for the same status can't both open one.
+pub fn open_automatic<'a>(
+    conn: &mut PgConnection,
+    project_status: &ProjectStatus,
+    now: DateTime<Utc>,
+) -> Result<Option<IncidentDetail>, DbError<'a>> {
+    let psid = &project_status.project_status_id;
+    conn.transaction::<_, DbError<'a>, _>(|conn| {
+        project_statuses::table
+            .filter(project_statuses::project_status_id.eq(psid))
+            .select(project_statuses::project_status_id)
+            .for_update()
+            .first::<String>(conn)?;
+        let open = incidents
+            .inner_join(incident_project_statuses::table)
+            .filter(automatic.eq(true))
+            .filter(state.ne(IncidentState::Resolved))
+            .filter(incident_project_statuses::project_status_id.eq(psid))
+            .select(incident_id)
+            .first::<String>(conn)
+            .optional()?;
+        if open.is_some() {
+            return Ok(None);
+        }
+
+        let incident = Incident::automatic(project_status, now);
+        let entry = IncidentTimelineEntry::new(
+            &incident,
+            format!(
+                "{}: {}",
+                project_status.name,
+                project_status.health_state.label()
+            ),
+            now,
+        );
+        let psids = [psid.clone()];
+        create(conn, incident, entry, &psids).map(Some)
+    })
+}
+
+/// Resolves the unresolved automatic incidents covering `psid` whose affected
+/// statuses are all healthy again.
+pub fn resolve_automatic<'a>(
+    conn: &mut PgConnection,
+    psid: &str,
+    now: DateTime<Utc>,
+) -> Result<Vec<IncidentDetail>, DbError<'a>> {
+    conn.transaction::<_, DbError<'a>, _>(|conn| {
+        let candidates = incidents
+            .inner_join(incident_project_statuses::table)
+            .filter(automatic.eq(true))
+            .filter(state.ne(IncidentState::Resolved))
+            .filter(incident_project_statuses::project_status_id.eq(psid))
+            .select(all_columns)
+            .get_results::<Incident>(conn)?;
+
+        let mut resolved = Vec::new();
+        for mut incident in candidates {
+            let states = incident_project_statuses::table
+                .inner_join(project_statuses::table)
+                .filter(incident_project_statuses::incident_id.eq(&incident.incident_id))
+                .filter(project_statuses::deleted_at.is_null())
+                .select(project_statuses::health_state)
+                .get_results::<HealthState>(conn)?;
+            if !states.iter().all(|s| s.is_healthy()) {
+                continue;
+            }
+
+            let id = incident.incident_id.clone();
+            incident.set_state(IncidentState::Resolved, now);
+            let entry = IncidentTimelineEntry::new(
+                &incident,
+                String::from("All affected statuses have recovered"),
+                now,
+            );
+            resolved.push(update(conn, &id, incident, Some(entry), None)?);
+        }
+        Ok(resolved)
+    })
+}
+
+/// Links `psids` to the incident, failing if any of them isn't a live status of the
+/// incident's project.
+fn link<'a>(
+    conn: &mut PgConnection,
+    incident: &Incident,
+    psids: &[String],
+) -> Result<(), DbError<'a>> {
+    if psids.is_empty() {
+        return Ok(());
+    }
+    let owned = project_statuses::table
+        .filter(project_statuses::project_status_id.eq_any(psids))
+        .filter(project_statuses::project_id.eq(&incident.project_id))
+        .filter(project_statuses::deleted_at.is_null())
+        .select(project_statuses::project_status_id)
+        .get_results::<String>(conn)?;
+    if let Some(foreign) = psids.iter().find(|psid| !owned.contains(psid)) {
+        return Err(DbError::ForeignProjectStatus(Cow::Owned(foreign.clone())));
+    }
+    let links = psids
+        .iter()
+        .map(|psid| IncidentProjectStatus {
+            incident_id: incident.incident_id.clone(),
+            project_status_id: psid.clone(),
+        })
+        .collect::<Vec<IncidentProjectStatus>>();
+    diesel::insert_into(incident_project_statuses::table)
+        .values(links)
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+    Ok(())
+}
+
+fn detail<'a>(conn: &mut PgConnection, incident: Incident) -> Result<IncidentDetail, DbError<'a>> {
+    let timeline = IncidentTimelineEntry::belonging_to(&incident)
+        .order(incident_timeline::created_at.asc())
+        .get_results::<IncidentTimelineEntry>(conn)?;
+    let project_status_ids = IncidentProjectStatus::belonging_to(&incident)
+        .select(incident_project_statuses::project_status_id)
+        .get_results::<String>(conn)?;
+    Ok(IncidentDetail {
+        incident,
+        timeline,
+        project_status_ids,
+    })
+}
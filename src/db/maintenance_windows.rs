@@ -0,0 +1,240 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::PgConnection;
+use std::borrow::Cow;
+
+use crate::schema::{
+    maintenance_window_project_statuses,
+    maintenance_windows::{all_columns, dsl::*},
+    project_status_history, project_statuses, projects,
+};
+use crate::{
+    errors::DbError,
+    models::{
+        health_state::HealthState,
+        maintenance_window::{
+            MaintenanceWindow, MaintenanceWindowDetail, MaintenanceWindowProjectStatus,
+        },
+        project_status::ProjectStatus,
+        project_status_history::{ProjectStatusHistory, ReportSource},
+    },
+};
+
+pub fn find_by_id<'a>(
+    conn: &mut PgConnection,
+    id: &str,
+) -> Result<Option<MaintenanceWindow>, DbError<'a>> {
+    let maintenance_window = maintenance_windows
+        .inner_join(projects::table)
+        .filter(maintenance_window_id.eq(id))
+        .filter(projects::deleted_at.is_null())
+        .select(all_columns)
+        .first::<MaintenanceWindow>(conn)
+        .optional()?;
+    Ok(maintenance_window)
+}
+
+pub fn find_by_project<'a>(
+    conn: &mut PgConnection,
+    pid: &str,
+) -> Result<Vec<MaintenanceWindow>, DbError<'a>> {
+    let results = maintenance_windows
+        .inner_join(projects::table)
+        .filter(project_id.eq(pid))
+        .filter(projects::deleted_at.is_null())
+        .select(all_columns)
+        .order(starts_at.desc())
+        .get_results::<MaintenanceWindow>(conn)?;
+    Ok(results)
+}
+
+/// Windows of the project that have not ended yet, soonest first.
+pub fn find_upcoming_by_project<'a>(
+    conn: &mut PgConnection,
+    pid: &str,
+    now: DateTime<Utc>,
+) -> Result<Vec<MaintenanceWindow>, DbError<'a>> {
+    let results = maintenance_windows
+        .inner_join(projects::table)
+        .filter(project_id.eq(pid))
+        .filter(projects::deleted_at.is_null())
+        .filter(ends_at.gt(now))
+        .select(all_columns)
+        .order(starts_at.asc())
+        .get_results::<MaintenanceWindow>(conn)?;
+    Ok(results)
+}
+
+pub fn find_detail<'a>(
+    conn: &mut PgConnection,
+    id: &str,
+) -> Result<Option<MaintenanceWindowDetail>, DbError<'a>> {
+    match find_by_id(conn, id)? {
+        Some(maintenance_window) => Ok(Some(detail(conn, maintenance_window)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn create<'a>(
+    conn: &mut PgConnection,
+    new_maintenance_window: MaintenanceWindow,
+    psids: &[String],
+) -> Result<MaintenanceWindowDetail, DbError<'a>> {
+    conn.transaction::<_, DbError<'a>, _>(|conn| {
+        let maintenance_window = diesel::insert_into(maintenance_windows)
+            .values(new_maintenance_window)
+            .get_result::<MaintenanceWindow>(conn)?;
+        link(conn, &maintenance_window, psids)?;
+        detail(conn, maintenance_window)
+    })
+}
+
+/// Updates the window, replacing its affected statuses with `psids` if given.
+pub fn update<'a>(
+    conn: &mut PgConnection,
+    id: &str,
+    updated_maintenance_window: MaintenanceWindow,
+    psids: Option<&[String]>,
+) -> Result<MaintenanceWindowDetail, DbError<'a>> {
+    conn.transaction::<_, DbError<'a>, _>(|conn| {
+        let maintenance_window =
+            diesel::update(maintenance_windows.filter(maintenance_window_id.eq(id)))
+                .set(updated_maintenance_window)
+                .get_result::<MaintenanceWindow>(conn)?;
+        if let Some(psids) = psids {
+            diesel::delete(
+                maintenance_window_project_statuses::table
+                    .filter(maintenance_window_project_statuses::maintenance_window_id.eq(id)),
+            )
+            .execute(conn)?;
+            link(conn, &maintenance_window, psids)?;
+        }
+        detail(conn, maintenance_window)
+    })
+}
+
+pub fn delete<'a>(conn: &mut PgConnection, id: &str) -> Result<(), DbError<'a>> {
+    diesel::delete(maintenance_windows.filter(maintenance_window_id.eq(id))).execute(conn)?;
+    Ok(())
+}
+
+/// Ids of the statuses covered by a window active at `now`.
+pub fn find_active_project_status_ids<'a>(
+    conn: &mut PgConnection,
+    now: DateTime<Utc>,
+) -> Result<Vec<String>, DbError<'a>> {
+    let results = maintenance_window_project_statuses::table
+        .inner_join(maintenance_windows)
+        .filter(starts_at.le(now))
+        .filter(ends_at.gt(now))
+        .select(maintenance_window_project_statuses::project_status_id)
+        .distinct()
+        .get_results::<String>(conn)?;
+    Ok(results)
+}
+
+pub fn is_active<'a>(
+    conn: &mut PgConnection,
+    psid: &str,
+    now: DateTime<Utc>,
+) -> Result<bool, DbError<'a>> {
+    let active = maintenance_window_project_statuses::table
+        .inner_join(maintenance_windows)
+        .filter(maintenance_window_project_statuses::project_status_id.eq(psid))
+        .filter(starts_at.le(now))
+        .filter(ends_at.gt(now))
+        .select(maintenance_window_id)
+        .first::<String>(conn)
+        .optional()?;
+    Ok(active.is_some())
+}
+
+/// Statuses that should be in maintenance at `now` but are not yet.
+pub fn find_starting<'a>(
+    conn: &mut PgConnection,
+    now: DateTime<Utc>,
+) -> Result<Vec<ProjectStatus>, DbError<'a>> {
+    let psids = find_active_project_status_ids(conn, now)?;
+    let results = project_statuses::table
+        .filter(project_statuses::project_status_id.eq_any(psids))
+        .filter(project_statuses::health_state.ne(HealthState::Maintenance))
+        .filter(project_statuses::deleted_at.is_null())
+        .get_results::<ProjectStatus>(conn)?;
+    Ok(results)
+}
+
+/// Statuses put into maintenance by a window that is no longer active.
+pub fn find_ending<'a>(
+    conn: &mut PgConnection,
+    now: DateTime<Utc>,
+) -> Result<Vec<ProjectStatus>, DbError<'a>> {
+    let psids = find_active_project_status_ids(conn, now)?;
+    let candidates = project_statuses::table
+        .filter(project_statuses::health_state.eq(HealthState::Maintenance))
+        .filter(project_statuses::project_status_id.ne_all(psids))
+        .filter(project_statuses::deleted_at.is_null())
+        .get_results::<ProjectStatus>(conn)?;
+
+    let mut results = Vec::new();
+    for project_status in candidates {
+        let psid = &project_status.project_status_id;
+        let entered = project_status_history::table
+            .filter(project_status_history::project_status_id.eq(psid))
+            .order(project_status_history::created_at.desc())
+            .first::<ProjectStatusHistory>(conn)
+            .optional()?;
+        // Maintenance set by hand is left alone.
+        if let Some(entered) = entered {
+            if entered.source == ReportSource::Maintenance
+                && entered.new_state == HealthState::Maintenance
+            {
+                results.push(project_status);
+            }
+        }
+    }
+    Ok(results)
+}
+
+fn link<'a>(
+    conn: &mut PgConnection,
+    maintenance_window: &MaintenanceWindow,
+    psids: &[String],
+) -> Result<(), DbError<'a>> {
+    if psids.is_empty() {
+        return Ok(());
+    }
+    let owned = project_statuses::table
+        .filter(project_statuses::project_status_id.eq_any(psids))
+        .filter(project_statuses::project_id.eq(&maintenance_window.project_id))
+        .filter(project_statuses::deleted_at.is_null())
+        .select(project_statuses::project_status_id)
+        .get_results::<String>(conn)?;
+    if let Some(foreign) = psids.iter().find(|psid| !owned.contains(psid)) {
+        return Err(DbError::ForeignProjectStatus(Cow::Owned(foreign.clone())));
+    }
+    let links = psids
+        .iter()
+        .map(|psid| MaintenanceWindowProjectStatus {
+            maintenance_window_id: maintenance_window.maintenance_window_id.clone(),
+            project_status_id: psid.clone(),
+        })
+        .collect::<Vec<MaintenanceWindowProjectStatus>>();
+    diesel::insert_into(maintenance_window_project_statuses::table)
+        .values(links)
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+    Ok(())
+}
+
+fn detail<'a>(
+    conn: &mut PgConnection,
+    maintenance_window: MaintenanceWindow,
+) -> Result<MaintenanceWindowDetail, DbError<'a>> {
+    let project_status_ids = MaintenanceWindowProjectStatus::belonging_to(&maintenance_window)
+        .select(maintenance_window_project_statuses::project_status_id)
+        .get_results::<String>(conn)?;
+    Ok(MaintenanceWindowDetail {
+        maintenance_window,
+        project_status_ids,
+    })
+}
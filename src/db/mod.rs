@@ -1,7 +1,18 @@
 use rocket_sync_db_pools::database;
 
+pub mod feeds;
+pub mod incidents;
+pub mod maintenance_windows;
+pub mod project_links;
+pub mod project_media;
+pub mod project_recipients;
+pub mod project_status_history;
+pub mod project_status_metrics;
 pub mod project_statuses;
 pub mod projects;
+pub mod status_page;
+pub mod tags;
+pub mod uptime;
 
 #[database("portfolio")]
 pub struct Db(diesel::PgConnection);
@@ -0,0 +1,77 @@
+use diesel::prelude::*;
+use diesel::PgConnection;
+
+use crate::schema::{
+    project_links::{all_columns, dsl::*},
+    projects as projects_table,
+};
+use crate::{
+    errors::DbError,
+    models::{project::Project, project_link::ProjectLink},
+};
+
+pub fn find_by_id<'a>(
+    conn: &mut PgConnection,
+    id: &str,
+) -> Result<Option<ProjectLink>, DbError<'a>> {
+    let link = project_links
+        .inner_join(projects_table::table)
+        .filter(project_link_id.eq(id))
+        .filter(projects_table::deleted_at.is_null())
+        .select(all_columns)
+        .first::<ProjectLink>(conn)
+        .optional()?;
+    Ok(link)
+}
+
+pub fn find_by_project<'a>(
+    conn: &mut PgConnection,
+    pid: &str,
+) -> Result<Vec<ProjectLink>, DbError<'a>> {
+    let results = project_links
+        .inner_join(projects_table::table)
+        .filter(project_id.eq(pid))
+        .filter(projects_table::deleted_at.is_null())
+        .select(all_columns)
+        .order(created_at.asc())
+        .get_results::<ProjectLink>(conn)?;
+    Ok(results)
+}
+
+/// The links of each of `projects`, oldest first.
+pub fn find_by_projects<'a>(
+    conn: &mut PgConnection,
+    projects: &[Project],
+) -> Result<Vec<Vec<ProjectLink>>, DbError<'a>> {
+    let results = ProjectLink::belonging_to(projects)
+        .order(created_at.asc())
+        .get_results::<ProjectLink>(conn)?
+        .grouped_by(projects);
+    Ok(results)
+}
+
+pub fn create<'a>(
+    conn: &mut PgConnection,
+    new_link: ProjectLink,
+) -> Result<ProjectLink, DbError<'a>> {
+    let link = diesel::insert_into(project_links)
+        .values(new_link)
+        .get_result::<ProjectLink>(conn)?;
+    Ok(link)
+}
+
+pub fn update<'a>(
+    conn: &mut PgConnection,
+    id: &str,
+    updated_link: ProjectLink,
+) -> Result<ProjectLink, DbError<'a>> {
+    let link = diesel::update(project_links.filter(project_link_id.eq(id)))
+        .set(updated_link)
+        .get_result::<ProjectLink>(conn)?;
+    Ok(link)
+}
+
+pub fn delete<'a>(conn: &mut PgConnection, id: &str) -> Result<(), DbError<'a>> {
+    diesel::delete(project_links.filter(project_link_id.eq(id))).execute(conn)?;
+    Ok(())
+}
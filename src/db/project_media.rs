@@ -0,0 +1,83 @@
+use diesel::prelude::*;
+use diesel::PgConnection;
+
+use crate::schema::{
+    project_media::{all_columns, dsl::*},
+    projects as projects_table,
+};
+use crate::{
+    errors::DbError,
+    models::{
+        project::Project,
+        project_media::{MediaKind, ProjectMedia},
+    },
+};
+
+pub fn find_by_id<'a>(
+    conn: &mut PgConnection,
+    id: &str,
+) -> Result<Option<ProjectMedia>, DbError<'a>> {
+    let media = project_media
+        .inner_join(projects_table::table)
+        .filter(project_media_id.eq(id))
+        .filter(projects_table::deleted_at.is_null())
+        .select(all_columns)
+        .first::<ProjectMedia>(conn)
+        .optional()?;
+    Ok(media)
+}
+
+pub fn find_by_project<'a>(
+    conn: &mut PgConnection,
+    pid: &str,
+) -> Result<Vec<ProjectMedia>, DbError<'a>> {
+    let results = project_media
+        .inner_join(projects_table::table)
+        .filter(project_id.eq(pid))
+        .filter(projects_table::deleted_at.is_null())
+        .select(all_columns)
+        .order(created_at.asc())
+        .get_results::<ProjectMedia>(conn)?;
+    Ok(results)
+}
+
+/// The media of each of `projects`, oldest first.
+pub fn find_by_projects<'a>(
+    conn: &mut PgConnection,
+    projects: &[Project],
+) -> Result<Vec<Vec<ProjectMedia>>, DbError<'a>> {
+    let results = ProjectMedia::belonging_to(projects)
+        .order(created_at.asc())
+        .get_results::<ProjectMedia>(conn)?
+        .grouped_by(projects);
+    Ok(results)
+}
+
+/// Stores the media and, if it's a cover, removes the cover it replaces, returning
+/// the removed media so its files can be deleted too.
+pub fn create<'a>(
+    conn: &mut PgConnection,
+    new_media: ProjectMedia,
+) -> Result<(ProjectMedia, Vec<ProjectMedia>), DbError<'a>> {
+    conn.transaction::<_, DbError<'a>, _>(|conn| {
+        let replaced = if new_media.kind == MediaKind::Cover {
+            diesel::delete(
+                project_media
+                    .filter(project_id.eq(&new_media.project_id))
+                    .filter(kind.eq(MediaKind::Cover)),
+            )
+            .get_results::<ProjectMedia>(conn)?
+        } else {
+            Vec::new()
+        };
+        let media = diesel::insert_into(project_media)
+            .values(new_media)
+            .get_result::<ProjectMedia>(conn)?;
+        Ok((media, replaced))
+    })
+}
+
+pub fn delete<'a>(conn: &mut PgConnection, id: &str) -> Result<(), DbError<'a>> {
+    diesel::delete(project_media.filter(project_media_id.eq(id))).execute(conn)?;
+    Ok(())
+}
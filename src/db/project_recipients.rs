@@ -0,0 +1,50 @@
+use diesel::prelude::*;
+use diesel::PgConnection;
+
+use crate::schema::{
+    project_recipients::{all_columns, dsl::*},
+    projects,
+};
+use crate::{errors::DbError, models::project_recipient::ProjectRecipient};
+
+pub fn find_by_id<'a>(
+    conn: &mut PgConnection,
+    id: &str,
+) -> Result<Option<ProjectRecipient>, DbError<'a>> {
+    let project_recipient = project_recipients
+        .inner_join(projects::table)
+        .filter(project_recipient_id.eq(id))
+        .filter(projects::deleted_at.is_null())
+        .select(all_columns)
+        .first::<ProjectRecipient>(conn)
+        .optional()?;
+    Ok(project_recipient)
+}
+
+pub fn find_by_project<'a>(
+    conn: &mut PgConnection,
+    pid: &str,
+) -> Result<Vec<ProjectRecipient>, DbError<'a>> {
+    let results = project_recipients
+        .inner_join(projects::table)
+        .filter(project_id.eq(pid))
+        .filter(projects::deleted_at.is_null())
+        .select(all_columns)
+        .get_results::<ProjectRecipient>(conn)?;
+    Ok(results)
+}
+
+pub fn create<'a>(
+    conn: &mut PgConnection,
+    new_project_recipient: ProjectRecipient,
+) -> Result<ProjectRecipient, DbError<'a>> {
+    let project_recipient = diesel::insert_into(project_recipients)
+        .values(new_project_recipient)
+        .get_result::<ProjectRecipient>(conn)?;
+    Ok(project_recipient)
+}
+
+pub fn delete<'a>(conn: &mut PgConnection, id: &str) -> Result<(), DbError<'a>> {
+    diesel::delete(project_recipients.filter(project_recipient_id.eq(id))).execute(conn)?;
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::PgConnection;
+
+use crate::schema::{
+    project_status_history::{all_columns, dsl::*},
+    project_statuses, projects,
+};
+use crate::{errors::DbError, models::project_status_history::ProjectStatusHistory};
+
+pub fn find_by_project_status<'a>(
+    conn: &mut PgConnection,
+    psid: &str,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<Vec<ProjectStatusHistory>, DbError<'a>> {
+    let mut query = project_status_history
+        .inner_join(project_statuses::table.inner_join(projects::table))
+        .filter(project_status_id.eq(psid))
+        .filter(project_statuses::deleted_at.is_null())
+        .filter(projects::deleted_at.is_null())
+        .select(all_columns)
+        .into_boxed();
+    if let Some(from) = from {
+        query = query.filter(created_at.ge(from));
+    }
+    if let Some(to) = to {
+        query = query.filter(created_at.lt(to));
+    }
+    let results = query
+        .order(created_at.asc())
+        .get_results::<ProjectStatusHistory>(conn)?;
+    Ok(results)
+}
+
+pub fn find_since<'a>(
+    conn: &mut PgConnection,
+    psids: &[String],
+    since: DateTime<Utc>,
+) -> Result<Vec<ProjectStatusHistory>, DbError<'a>> {
+    let results = project_status_history
+        .inner_join(project_statuses::table.inner_join(projects::table))
+        .filter(project_status_id.eq_any(psids))
+        .filter(project_statuses::deleted_at.is_null())
+        .filter(projects::deleted_at.is_null())
+        .filter(created_at.gt(since))
+        .select(all_columns)
+        .order(created_at.asc())
+        .get_results::<ProjectStatusHistory>(conn)?;
+    Ok(results)
+}
+
+pub fn create<'a>(
+    conn: &mut PgConnection,
+    new_project_status_history: ProjectStatusHistory,
+) -> Result<ProjectStatusHistory, DbError<'a>> {
+    let history = diesel::insert_into(project_status_history)
+        .values(new_project_status_history)
+        .get_result::<ProjectStatusHistory>(conn)?;
+    Ok(history)
+}
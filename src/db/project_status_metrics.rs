@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::PgConnection;
+
+use crate::schema::{
+    project_status_metrics::{all_columns, dsl::*},
+    project_statuses, projects,
+};
+use crate::{errors::DbError, models::project_status_metric::ProjectStatusMetric};
+
+pub fn find_by_project_status<'a>(
+    conn: &mut PgConnection,
+    psid: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<ProjectStatusMetric>, DbError<'a>> {
+    let results = project_status_metrics
+        .inner_join(project_statuses::table.inner_join(projects::table))
+        .filter(project_status_id.eq(psid))
+        .filter(project_statuses::deleted_at.is_null())
+        .filter(projects::deleted_at.is_null())
+        .filter(created_at.ge(from))
+        .filter(created_at.lt(to))
+        .select(all_columns)
+        .order(created_at.asc())
+        .get_results::<ProjectStatusMetric>(conn)?;
+    Ok(results)
+}
+
+/// Deletes the samples taken before `before`.
+pub fn purge<'a>(conn: &mut PgConnection, before: DateTime<Utc>) -> Result<usize, DbError<'a>> {
+    let purged =
+        diesel::delete(project_status_metrics.filter(created_at.lt(before))).execute(conn)?;
+    Ok(purged)
+}
+
+pub fn create<'a>(
+    conn: &mut PgConnection,
+    new_project_status_metric: ProjectStatusMetric,
+) -> Result<ProjectStatusMetric, DbError<'a>> {
+    let metric = diesel::insert_into(project_status_metrics)
+        .values(new_project_status_metric)
+        .get_result::<ProjectStatusMetric>(conn)?;
+    Ok(metric)
+}
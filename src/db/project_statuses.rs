@@ -1,12 +1,38 @@
+use chrono::{DateTime, Utc};
+use diesel::pg::Pg;
 use diesel::prelude::*;
 use diesel::PgConnection;
 
-use crate::schema::project_statuses::dsl::*;
-use crate::{errors::DbError, models::project_status::ProjectStatus};
+use super::{project_status_history, project_status_metrics};
+use crate::schema::{
+    project_statuses::{dsl::*, BoxedQuery},
+    projects,
+};
+use crate::{
+    errors::DbError,
+    models::{
+        project::Project,
+        project_status::{ProjectStatus, ProjectStatusFilter},
+        project_status_history::ProjectStatusHistory,
+        project_status_metric::ProjectStatusMetric,
+        sort::Sort,
+        visibility::Visibility,
+    },
+    pagination::{Page, PageRequest},
+};
 
-pub fn find<'a>(conn: &mut PgConnection) -> Result<Vec<ProjectStatus>, DbError<'a>> {
-    let results = project_statuses.get_results::<ProjectStatus>(conn)?;
-    Ok(results)
+pub fn find<'a>(
+    conn: &mut PgConnection,
+    filter: &ProjectStatusFilter,
+    sort: Sort,
+    published_only: bool,
+    page: PageRequest,
+) -> Result<Page<ProjectStatus>, DbError<'a>> {
+    let results = query(filter, sort, published_only)
+        .limit(page.fetch_limit())
+        .offset(page.offset)
+        .get_results::<ProjectStatus>(conn)?;
+    Ok(Page::new(results, page))
 }
 
 pub fn find_by_id<'a>(
@@ -15,6 +41,7 @@ pub fn find_by_id<'a>(
 ) -> Result<Option<ProjectStatus>, DbError<'a>> {
     let project_status = project_statuses
         .filter(project_status_id.eq(id))
+        .filter(deleted_at.is_null())
         .first::<ProjectStatus>(conn)
         .optional()?;
     Ok(project_status)
@@ -26,10 +53,23 @@ pub fn find_by_project<'a>(
 ) -> Result<Vec<ProjectStatus>, DbError<'a>> {
     let results = project_statuses
         .filter(project_id.eq(pid))
+        .filter(deleted_at.is_null())
         .get_results::<ProjectStatus>(conn)?;
     Ok(results)
 }
 
+pub fn find_checks<'a>(
+    conn: &mut PgConnection,
+) -> Result<Vec<(ProjectStatus, Project)>, DbError<'a>> {
+    let results = project_statuses
+        .inner_join(projects::table)
+        .filter(check_enabled.eq(true))
+        .filter(deleted_at.is_null())
+        .filter(projects::deleted_at.is_null())
+        .get_results::<(ProjectStatus, Project)>(conn)?;
+    Ok(results)
+}
+
 pub fn create<'a>(
     conn: &mut PgConnection,
     new_project_status: ProjectStatus,
@@ -51,7 +91,123 @@ pub fn update<'a>(
     Ok(project_status)
 }
 
+/// Locks the status with `SELECT ... FOR UPDATE` and hands the locked row to `apply`,
+/// which returns the status to save and the `history` entry of the report if any.
+/// Saves both along with the `metric` sample in the same transaction, so concurrent
+/// reports are applied one after the other. Returns the locked and the saved status.
+pub fn update_with_report<'a, F>(
+    conn: &mut PgConnection,
+    id: &str,
+    apply: F,
+    metric: Option<ProjectStatusMetric>,
+) -> Result<(ProjectStatus, ProjectStatus), DbError<'a>>
+where
+    F: FnOnce(
+        &mut PgConnection,
+        &ProjectStatus,
+    ) -> Result<(ProjectStatus, Option<ProjectStatusHistory>), DbError<'a>>,
+{
+    conn.transaction::<_, DbError<'a>, _>(|conn| {
+        let previous = project_statuses
+            .filter(project_status_id.eq(id))
+            .for_update()
+            .first::<ProjectStatus>(conn)?;
+        let (updated_project_status, history) = apply(conn, &previous)?;
+        // Most reports leave the status as it was; there is nothing to write then.
+        let project_status = if updated_project_status == previous {
+            previous.clone()
+        } else {
+            update(conn, id, updated_project_status)?
+        };
+        if let Some(history) = history {
+            project_status_history::create(conn, history)?;
+        }
+        if let Some(metric) = metric {
+            project_status_metrics::create(conn, metric)?;
+        }
+        Ok((previous, project_status))
+    })
+}
+
+/// Statuses in the trash, most recently deleted first.
+pub fn find_deleted<'a>(conn: &mut PgConnection) -> Result<Vec<ProjectStatus>, DbError<'a>> {
+    let results = project_statuses
+        .filter(deleted_at.is_not_null())
+        .order((deleted_at.desc(), project_status_id.asc()))
+        .get_results::<ProjectStatus>(conn)?;
+    Ok(results)
+}
+
+/// The status `id` if it's in the trash.
+pub fn find_deleted_by_id<'a>(
+    conn: &mut PgConnection,
+    id: &str,
+) -> Result<Option<ProjectStatus>, DbError<'a>> {
+    let project_status = project_statuses
+        .filter(project_status_id.eq(id))
+        .filter(deleted_at.is_not_null())
+        .first::<ProjectStatus>(conn)
+        .optional()?;
+    Ok(project_status)
+}
+
+/// Moves the status to the trash.
 pub fn delete<'a>(conn: &mut PgConnection, id: &str) -> Result<(), DbError<'a>> {
-    diesel::delete(project_statuses.filter(project_status_id.eq(id))).execute(conn)?;
+    diesel::update(project_statuses.filter(project_status_id.eq(id)))
+        .set(deleted_at.eq(Utc::now()))
+        .execute(conn)?;
     Ok(())
 }
+
+pub fn restore<'a>(conn: &mut PgConnection, id: &str) -> Result<ProjectStatus, DbError<'a>> {
+    let project_status = diesel::update(project_statuses.filter(project_status_id.eq(id)))
+        .set(deleted_at.eq(None::<DateTime<Utc>>))
+        .get_result::<ProjectStatus>(conn)?;
+    Ok(project_status)
+}
+
+/// Permanently deletes the statuses that went to the trash before `before`.
+pub fn purge<'a>(conn: &mut PgConnection, before: DateTime<Utc>) -> Result<usize, DbError<'a>> {
+    let purged = diesel::delete(project_statuses.filter(deleted_at.lt(before))).execute(conn)?;
+    Ok(purged)
+}
+
+/// Statuses matching `filter` in `sort` order, ties broken by id so pages are stable.
+/// `published_only` leaves out the statuses of projects that aren't published.
+fn query<'a>(
+    filter: &'a ProjectStatusFilter,
+    sort: Sort,
+    published_only: bool,
+) -> BoxedQuery<'a, Pg> {
+    let mut query = project_statuses.filter(deleted_at.is_null()).into_boxed();
+    if published_only {
+        query = query.filter(
+            project_id.eq_any(
+                projects::table
+                    .filter(projects::visibility.eq(Visibility::Published))
+                    .select(projects::project_id),
+            ),
+        );
+    }
+    if let Some(pid) = &filter.project_id {
+        query = query.filter(project_id.eq(pid));
+    }
+    if let Some(healthy) = filter.is_healthy {
+        query = query.filter(is_healthy.eq(healthy));
+    }
+    if let Some(state) = filter.health_state {
+        query = query.filter(health_state.eq(state));
+    }
+    if let Some(enabled) = filter.check_enabled {
+        query = query.filter(check_enabled.eq(enabled));
+    }
+    let query = match sort {
+        Sort::CreatedAt => query.order(created_at.asc()),
+        Sort::CreatedAtDesc => query.order(created_at.desc()),
+        Sort::UpdatedAt => query.order(updated_at.asc()),
+        Sort::UpdatedAtDesc => query.order(updated_at.desc()),
+        Sort::Name => query.order(name.asc()),
+        Sort::NameDesc => query.order(name.desc()),
+    };
+    query.then_order_by(project_status_id.asc())
+}
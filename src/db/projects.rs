@@ -1,52 +1,503 @@
+use chrono::{DateTime, Utc};
+use diesel::pg::Pg;
 use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Bool, Text};
 use diesel::PgConnection;
 
 use crate::errors::DbError;
-use crate::{models::project::Project, schema::projects::dsl::*};
+use crate::{
+    models::{
+        project::{
+            Project, ProjectChangeset, ProjectDetail, ProjectFilter, ProjectSearchDetail,
+            ProjectSearchResult, ProjectSlugRedirect,
+        },
+        project_media::ProjectMedia,
+        project_status::ProjectStatus,
+        sort::Sort,
+        tag::Tag,
+        visibility::Visibility,
+    },
+    pagination::{Page, PageRequest},
+    schema::{
+        project_media, project_slug_redirects, project_statuses, project_tags,
+        projects::{dsl::*, BoxedQuery},
+        tags,
+    },
+};
 
-pub fn find<'a>(conn: &mut PgConnection) -> Result<Vec<Project>, DbError<'a>> {
-    let results = projects.get_results::<Project>(conn)?;
+pub fn find<'a>(
+    conn: &mut PgConnection,
+    filter: &ProjectFilter,
+    sort: Option<Sort>,
+    page: PageRequest,
+) -> Result<Page<Project>, DbError<'a>> {
+    let results = query(filter, sort)
+        .limit(page.fetch_limit())
+        .offset(page.offset)
+        .get_results::<Project>(conn)?;
+    Ok(Page::new(results, page))
+}
+
+/// Every published project, in position order.
+pub fn find_all<'a>(conn: &mut PgConnection) -> Result<Vec<Project>, DbError<'a>> {
+    let filter = ProjectFilter {
+        visibility: Some(Visibility::Published),
+        ..ProjectFilter::default()
+    };
+    let results = query(&filter, None).get_results::<Project>(conn)?;
+    Ok(results)
+}
+
+pub fn find_by_ids<'a>(
+    conn: &mut PgConnection,
+    ids: &[String],
+) -> Result<Vec<Project>, DbError<'a>> {
+    let results = projects
+        .filter(project_id.eq_any(ids))
+        .filter(deleted_at.is_null())
+        .get_results::<Project>(conn)?;
     Ok(results)
 }
 
 pub fn find_by_id<'a>(conn: &mut PgConnection, id: &str) -> Result<Option<Project>, DbError<'a>> {
     let project = projects
         .filter(project_id.eq(id))
+        .filter(deleted_at.is_null())
+        .first::<Project>(conn)
+        .optional()?;
+    Ok(project)
+}
+
+/// The project `id` if it's in the trash.
+pub fn find_deleted_by_id<'a>(
+    conn: &mut PgConnection,
+    id: &str,
+) -> Result<Option<Project>, DbError<'a>> {
+    let project = projects
+        .filter(project_id.eq(id))
+        .filter(deleted_at.is_not_null())
         .first::<Project>(conn)
         .optional()?;
     Ok(project)
 }
 
+/// Whether the project `id` exists and can be shown, which unauthenticated requests
+/// may only do for published and unlisted projects.
+pub fn is_visible<'a>(
+    conn: &mut PgConnection,
+    id: &str,
+    authenticated: bool,
+) -> Result<bool, DbError<'a>> {
+    let project = find_by_id(conn, id)?;
+    Ok(project.is_some_and(|p| authenticated || p.visibility.is_public()))
+}
+
+/// A page of projects with their tags and, when asked for, their health and statuses.
+pub fn find_details<'a>(
+    conn: &mut PgConnection,
+    filter: &ProjectFilter,
+    sort: Option<Sort>,
+    page: PageRequest,
+    with_health: bool,
+    with_statuses: bool,
+) -> Result<Page<ProjectDetail>, DbError<'a>> {
+    let Page { items, next_cursor } = find(conn, filter, sort, page)?;
+    Ok(Page {
+        items: details(conn, items, with_health, with_statuses)?,
+        next_cursor,
+    })
+}
+
+pub fn find_detail_by_slug<'a>(
+    conn: &mut PgConnection,
+    project_slug: &str,
+    with_health: bool,
+    with_statuses: bool,
+) -> Result<Option<ProjectDetail>, DbError<'a>> {
+    let project = projects
+        .filter(slug.eq(project_slug))
+        .filter(deleted_at.is_null())
+        .first::<Project>(conn)
+        .optional()?;
+    match project {
+        Some(project) => Ok(details(conn, vec![project], with_health, with_statuses)?.pop()),
+        None => Ok(None),
+    }
+}
+
+/// The current slug of the project that used to go by `old_slug`.
+pub fn find_slug_redirect<'a>(
+    conn: &mut PgConnection,
+    old_slug: &str,
+) -> Result<Option<String>, DbError<'a>> {
+    let current = project_slug_redirects::table
+        .inner_join(projects)
+        .filter(project_slug_redirects::slug.eq(old_slug))
+        .filter(deleted_at.is_null())
+        .select(slug)
+        .first::<String>(conn)
+        .optional()?;
+    Ok(current)
+}
+
+pub fn find_detail<'a>(
+    conn: &mut PgConnection,
+    id: &str,
+    with_health: bool,
+    with_statuses: bool,
+) -> Result<Option<ProjectDetail>, DbError<'a>> {
+    match find_by_id(conn, id)? {
+        Some(project) => Ok(details(conn, vec![project], with_health, with_statuses)?.pop()),
+        None => Ok(None),
+    }
+}
+
+/// Projects whose name, description or repository match the web search style
+/// `terms`, best ranked first. `search_vector` is a generated column left out of the
+/// schema, hence the raw query.
+pub fn search<'a>(
+    conn: &mut PgConnection,
+    terms: &str,
+    published_only: bool,
+    page: PageRequest,
+) -> Result<Page<ProjectSearchResult>, DbError<'a>> {
+    let (start, stop) = (
+        ProjectSearchResult::START_SEL,
+        ProjectSearchResult::STOP_SEL,
+    );
+    let results = diesel::sql_query(format!(
+        "SELECT projects.*, ts_rank(search_vector, query) AS rank, \
+         ts_headline('english', name, query, 'StartSel={start}, StopSel={stop}, HighlightAll=true') AS name_highlight, \
+         ts_headline('english', coalesce(description, ''), query, 'StartSel={start}, StopSel={stop}') AS description_highlight \
+         FROM projects, websearch_to_tsquery('english', $1) query \
+         WHERE search_vector @@ query AND deleted_at IS NULL \
+         AND (NOT $2 OR visibility = 'published') \
+         ORDER BY rank DESC, project_id \
+         LIMIT $3 OFFSET $4",
+    ))
+    .bind::<Text, _>(terms)
+    .bind::<Bool, _>(published_only)
+    .bind::<BigInt, _>(page.fetch_limit())
+    .bind::<BigInt, _>(page.offset)
+    .load::<ProjectSearchResult>(conn)?
+    .into_iter()
+    .map(ProjectSearchResult::escape_highlights)
+    .collect();
+    Ok(Page::new(results, page))
+}
+
+/// Like `search`, with the tags, links and media of every project found.
+pub fn search_details<'a>(
+    conn: &mut PgConnection,
+    terms: &str,
+    published_only: bool,
+    page: PageRequest,
+) -> Result<Page<ProjectSearchDetail>, DbError<'a>> {
+    let Page { items, next_cursor } = search(conn, terms, published_only, page)?;
+    let (found, hits): (Vec<Project>, Vec<_>) = items
+        .into_iter()
+        .map(|r| {
+            (
+                r.project,
+                (r.rank, r.name_highlight, r.description_highlight),
+            )
+        })
+        .unzip();
+    let items = details(conn, found, false, false)?
+        .into_iter()
+        .zip(hits)
+        .map(
+            |(detail, (rank, name_highlight, description_highlight))| ProjectSearchDetail {
+                detail,
+                rank,
+                name_highlight,
+                description_highlight,
+            },
+        )
+        .collect();
+    Ok(Page { items, next_cursor })
+}
+
 pub fn find_by_name<'a>(
     conn: &mut PgConnection,
     project_name: &str,
 ) -> Result<Option<Project>, DbError<'a>> {
     let project = projects
         .filter(name.eq(project_name))
+        .filter(deleted_at.is_null())
         .first::<Project>(conn)
         .optional()?;
     Ok(project)
 }
 
-pub fn create<'a>(conn: &mut PgConnection, new_project: Project) -> Result<Project, DbError<'a>> {
-    let project = diesel::insert_into(projects)
-        .values(new_project)
-        .get_result::<Project>(conn)?;
-    Ok(project)
+/// Creates the project tagged with the tags called `tag_names` after every other
+/// project, numbering its slug if another project already uses it.
+pub fn create<'a>(
+    conn: &mut PgConnection,
+    mut new_project: Project,
+    tag_names: &[String],
+) -> Result<ProjectDetail, DbError<'a>> {
+    conn.transaction::<_, DbError<'a>, _>(|conn| {
+        new_project.slug = unique_slug(conn, &new_project.slug, &new_project.project_id)?;
+        new_project.position = projects
+            .select(diesel::dsl::max(position))
+            .first::<Option<i32>>(conn)?
+            .map_or(0, |last| last + 1);
+        let project = diesel::insert_into(projects)
+            .values(new_project)
+            .get_result::<Project>(conn)?;
+        let project_tags = super::tags::set_for_project(conn, &project.project_id, tag_names)?;
+        Ok(ProjectDetail::new(
+            project,
+            project_tags,
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+            false,
+            false,
+        ))
+    })
 }
 
+/// Updates the project, replacing its tags with the ones called `tag_names` if given.
+/// When the slug changes, the previous one is kept as a redirect.
 pub fn update<'a>(
     conn: &mut PgConnection,
     id: &str,
-    updated_project: Project,
-) -> Result<Project, DbError<'a>> {
-    let project = diesel::update(projects.filter(project_id.eq(id)))
-        .set(updated_project)
-        .get_result::<Project>(conn)?;
-    Ok(project)
+    mut updated_project: Project,
+    tag_names: Option<&[String]>,
+) -> Result<ProjectDetail, DbError<'a>> {
+    conn.transaction::<_, DbError<'a>, _>(|conn| {
+        let previous_slug = projects
+            .filter(project_id.eq(id))
+            .select(slug)
+            .first::<String>(conn)?;
+        if updated_project.slug != previous_slug {
+            updated_project.slug = unique_slug(conn, &updated_project.slug, id)?;
+        }
+        if updated_project.slug != previous_slug {
+            diesel::insert_into(project_slug_redirects::table)
+                .values(ProjectSlugRedirect {
+                    slug: previous_slug,
+                    project_id: id.to_string(),
+                    created_at: Utc::now(),
+                })
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+            diesel::delete(
+                project_slug_redirects::table
+                    .filter(project_slug_redirects::slug.eq(&updated_project.slug)),
+            )
+            .execute(conn)?;
+        }
+        let project = diesel::update(projects.filter(project_id.eq(id)))
+            .set(ProjectChangeset::from(&updated_project))
+            .get_result::<Project>(conn)?;
+        if let Some(tag_names) = tag_names {
+            super::tags::set_for_project(conn, id, tag_names)?;
+        }
+        Ok(details(conn, vec![project], false, false)?.remove(0))
+    })
+}
+
+/// Moves the projects `ids` to the front in the given order, keeping the others after
+/// them in their current order. Positions are rewritten in a single transaction so
+/// concurrent readers never see a half-applied order.
+pub fn reorder<'a>(conn: &mut PgConnection, ids: &[String]) -> Result<(), DbError<'a>> {
+    conn.transaction::<_, DbError<'a>, _>(|conn| {
+        let others = projects
+            .filter(project_id.ne_all(ids))
+            .filter(deleted_at.is_null())
+            .order((position.asc(), project_id.asc()))
+            .select(project_id)
+            .get_results::<String>(conn)?;
+        for (new_position, id) in ids.iter().chain(others.iter()).enumerate() {
+            diesel::update(projects.filter(project_id.eq(id)))
+                .set(position.eq(new_position as i32))
+                .execute(conn)?;
+        }
+        Ok(())
+    })
+}
+
+/// Projects in the trash, most recently deleted first.
+pub fn find_deleted<'a>(conn: &mut PgConnection) -> Result<Vec<Project>, DbError<'a>> {
+    let results = projects
+        .filter(deleted_at.is_not_null())
+        .order((deleted_at.desc(), project_id.asc()))
+        .get_results::<Project>(conn)?;
+    Ok(results)
 }
 
+/// Moves the project to the trash along with its statuses, which share its
+/// `deleted_at` so they can be restored with it.
 pub fn delete<'a>(conn: &mut PgConnection, id: &str) -> Result<(), DbError<'a>> {
-    diesel::delete(projects.filter(project_id.eq(id))).execute(conn)?;
-    Ok(())
+    let now = Utc::now();
+    conn.transaction::<_, DbError<'a>, _>(|conn| {
+        diesel::update(projects.filter(project_id.eq(id)))
+            .set(deleted_at.eq(now))
+            .execute(conn)?;
+        diesel::update(
+            project_statuses::table
+                .filter(project_statuses::project_id.eq(id))
+                .filter(project_statuses::deleted_at.is_null()),
+        )
+        .set(project_statuses::deleted_at.eq(now))
+        .execute(conn)?;
+        Ok(())
+    })
+}
+
+/// Takes the project out of the trash along with the statuses deleted with it, or
+/// returns `None` if it isn't in the trash.
+pub fn restore<'a>(conn: &mut PgConnection, id: &str) -> Result<Option<Project>, DbError<'a>> {
+    conn.transaction::<_, DbError<'a>, _>(|conn| {
+        let deleted = projects
+            .filter(project_id.eq(id))
+            .select(deleted_at)
+            .first::<Option<DateTime<Utc>>>(conn)
+            .optional()?
+            .flatten();
+        let deleted = match deleted {
+            Some(deleted) => deleted,
+            None => return Ok(None),
+        };
+        let project = diesel::update(projects.filter(project_id.eq(id)))
+            .set(deleted_at.eq(None::<DateTime<Utc>>))
+            .get_result::<Project>(conn)?;
+        diesel::update(
+            project_statuses::table
+                .filter(project_statuses::project_id.eq(id))
+                .filter(project_statuses::deleted_at.eq(deleted)),
+        )
+        .set(project_statuses::deleted_at.eq(None::<DateTime<Utc>>))
+        .execute(conn)?;
+        Ok(Some(project))
+    })
+}
+
+/// Permanently deletes the projects that went to the trash before `before`,
+/// returning their media so its files can be removed too.
+pub fn purge<'a>(
+    conn: &mut PgConnection,
+    before: DateTime<Utc>,
+) -> Result<Vec<ProjectMedia>, DbError<'a>> {
+    conn.transaction::<_, DbError<'a>, _>(|conn| {
+        let expired = projects
+            .filter(deleted_at.lt(before))
+            .select(project_id)
+            .get_results::<String>(conn)?;
+        let media = project_media::table
+            .filter(project_media::project_id.eq_any(&expired))
+            .get_results::<ProjectMedia>(conn)?;
+        diesel::delete(
+            project_statuses::table.filter(project_statuses::project_id.eq_any(&expired)),
+        )
+        .execute(conn)?;
+        diesel::delete(projects.filter(project_id.eq_any(&expired))).execute(conn)?;
+        Ok(media)
+    })
+}
+
+/// `base`, or the first of `base-2`, `base-3`... that no other project uses as its
+/// slug or as a redirect.
+fn unique_slug<'a>(conn: &mut PgConnection, base: &str, pid: &str) -> Result<String, DbError<'a>> {
+    let mut candidate = base.to_string();
+    let mut n = 1;
+    while is_slug_taken(conn, &candidate, pid)? {
+        n += 1;
+        candidate = format!("{}-{}", base, n);
+    }
+    Ok(candidate)
+}
+
+fn is_slug_taken<'a>(
+    conn: &mut PgConnection,
+    candidate: &str,
+    pid: &str,
+) -> Result<bool, DbError<'a>> {
+    let by_project = diesel::select(diesel::dsl::exists(
+        projects
+            .filter(slug.eq(candidate))
+            .filter(project_id.ne(pid)),
+    ))
+    .get_result::<bool>(conn)?;
+    let by_redirect = diesel::select(diesel::dsl::exists(
+        project_slug_redirects::table
+            .filter(project_slug_redirects::slug.eq(candidate))
+            .filter(project_slug_redirects::project_id.ne(pid)),
+    ))
+    .get_result::<bool>(conn)?;
+    Ok(by_project || by_redirect)
+}
+
+/// Loads the tags, links and media of `items` and, when needed, their statuses.
+fn details<'a>(
+    conn: &mut PgConnection,
+    items: Vec<Project>,
+    with_health: bool,
+    with_statuses: bool,
+) -> Result<Vec<ProjectDetail>, DbError<'a>> {
+    let project_tags = super::tags::find_by_projects(conn, &items)?;
+    let links = super::project_links::find_by_projects(conn, &items)?;
+    let media = super::project_media::find_by_projects(conn, &items)?;
+    let statuses = if with_health || with_statuses {
+        ProjectStatus::belonging_to(&items)
+            .filter(project_statuses::deleted_at.is_null())
+            .get_results::<ProjectStatus>(conn)?
+            .grouped_by(&items)
+    } else {
+        items.iter().map(|_| Vec::new()).collect()
+    };
+    let results = items
+        .into_iter()
+        .zip(project_tags)
+        .zip(links)
+        .zip(media)
+        .zip(statuses)
+        .map(|((((project, project_tags), links), media), statuses)| {
+            ProjectDetail::new(
+                project,
+                project_tags,
+                links,
+                media,
+                statuses,
+                with_health,
+                with_statuses,
+            )
+        })
+        .collect();
+    Ok(results)
+}
+
+/// Projects matching `filter` in `sort` order, or in position order without one, ties
+/// broken by id so pages are stable.
+fn query<'a>(filter: &'a ProjectFilter, sort: Option<Sort>) -> BoxedQuery<'a, Pg> {
+    let mut query = projects.filter(deleted_at.is_null()).into_boxed();
+    if let Some(project_name) = &filter.name {
+        query = query.filter(name.eq(project_name));
+    }
+    if let Some(tag) = &filter.tag {
+        let tagged = project_tags::table
+            .inner_join(tags::table)
+            .filter(tags::name.eq(Tag::normalize(tag)))
+            .select(project_tags::project_id);
+        query = query.filter(project_id.eq_any(tagged));
+    }
+    if let Some(is_featured) = filter.featured {
+        query = query.filter(featured.eq(is_featured));
+    }
+    if let Some(project_visibility) = filter.visibility {
+        query = query.filter(visibility.eq(project_visibility));
+    }
+    let query = match sort {
+        None => query.order(position.asc()),
+        Some(Sort::CreatedAt) => query.order(created_at.asc()),
+        Some(Sort::CreatedAtDesc) => query.order(created_at.desc()),
+        Some(Sort::UpdatedAt) => query.order(updated_at.asc()),
+        Some(Sort::UpdatedAtDesc) => query.order(updated_at.desc()),
+        Some(Sort::Name) => query.order(name.asc()),
+        Some(Sort::NameDesc) => query.order(name.desc()),
+    };
+    query.then_order_by(project_id.asc())
 }
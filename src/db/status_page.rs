@@ -0,0 +1,55 @@
+use chrono::{Duration, Utc};
+use diesel::PgConnection;
+
+use super::{incidents, project_statuses, projects, uptime};
+use crate::{
+    errors::DbError,
+    models::{
+        project::Project,
+        status_page::{StatusPageProject, StatusPageStatus},
+    },
+};
+
+/// How far back resolved incidents are shown.
+pub const RECENT_INCIDENT_DAYS: i64 = 14;
+
+pub fn find<'a>(conn: &mut PgConnection) -> Result<Vec<StatusPageProject>, DbError<'a>> {
+    projects::find_all(conn)?
+        .into_iter()
+        .map(|project| load(conn, project))
+        .collect()
+}
+
+/// The project called `project_name`, unless it's a draft or archived.
+pub fn find_by_project_name<'a>(
+    conn: &mut PgConnection,
+    project_name: &str,
+) -> Result<Option<StatusPageProject>, DbError<'a>> {
+    match projects::find_by_name(conn, project_name)? {
+        Some(project) if project.visibility.is_public() => Ok(Some(load(conn, project)?)),
+        _ => Ok(None),
+    }
+}
+
+fn load<'a>(conn: &mut PgConnection, project: Project) -> Result<StatusPageProject, DbError<'a>> {
+    let statuses = project_statuses::find_by_project(conn, &project.project_id)?;
+    let daily_uptimes = uptime::find_daily(conn, &statuses)?;
+    let since = Utc::now() - Duration::days(RECENT_INCIDENT_DAYS);
+    let incidents = incidents::find_recent_by_project(conn, &project.project_id, since)?;
+
+    let health = project.rollup_policy.apply(&statuses);
+    let project_statuses = statuses
+        .into_iter()
+        .zip(daily_uptimes)
+        .map(|(project_status, daily_uptime)| StatusPageStatus {
+            project_status,
+            daily_uptime,
+        })
+        .collect();
+    Ok(StatusPageProject {
+        project,
+        health,
+        project_statuses,
+        incidents,
+    })
+}
@@ -0,0 +1,111 @@
+use diesel::prelude::*;
+use diesel::PgConnection;
+use uuid::Uuid;
+
+use crate::schema::{project_tags, tags::dsl::*};
+use crate::{
+    errors::DbError,
+    models::{
+        project::Project,
+        tag::{ProjectTag, Tag},
+    },
+};
+
+pub fn find<'a>(conn: &mut PgConnection) -> Result<Vec<Tag>, DbError<'a>> {
+    let results = tags.order(name.asc()).get_results::<Tag>(conn)?;
+    Ok(results)
+}
+
+pub fn find_by_id<'a>(conn: &mut PgConnection, id: &str) -> Result<Option<Tag>, DbError<'a>> {
+    let tag = tags.filter(tag_id.eq(id)).first::<Tag>(conn).optional()?;
+    Ok(tag)
+}
+
+/// The tags of each of `projects`, by name.
+pub fn find_by_projects<'a>(
+    conn: &mut PgConnection,
+    projects: &[Project],
+) -> Result<Vec<Vec<Tag>>, DbError<'a>> {
+    let links = ProjectTag::belonging_to(projects)
+        .inner_join(tags)
+        .order(name.asc())
+        .get_results::<(ProjectTag, Tag)>(conn)?;
+    let results = projects
+        .iter()
+        .map(|project| {
+            links
+                .iter()
+                .filter(|(link, _)| link.project_id == project.project_id)
+                .map(|(_, tag)| tag.clone())
+                .collect()
+        })
+        .collect();
+    Ok(results)
+}
+
+pub fn create<'a>(conn: &mut PgConnection, new_tag: Tag) -> Result<Tag, DbError<'a>> {
+    let tag = diesel::insert_into(tags)
+        .values(new_tag)
+        .get_result::<Tag>(conn)?;
+    Ok(tag)
+}
+
+pub fn update<'a>(conn: &mut PgConnection, id: &str, updated_tag: Tag) -> Result<Tag, DbError<'a>> {
+    let tag = diesel::update(tags.filter(tag_id.eq(id)))
+        .set(updated_tag)
+        .get_result::<Tag>(conn)?;
+    Ok(tag)
+}
+
+pub fn delete<'a>(conn: &mut PgConnection, id: &str) -> Result<(), DbError<'a>> {
+    diesel::delete(tags.filter(tag_id.eq(id))).execute(conn)?;
+    Ok(())
+}
+
+/// Replaces the tags of the project with the ones called `names`, creating those
+/// that don't exist yet.
+pub fn set_for_project<'a>(
+    conn: &mut PgConnection,
+    pid: &str,
+    names: &[String],
+) -> Result<Vec<Tag>, DbError<'a>> {
+    let names = names
+        .iter()
+        .map(|n| Tag::normalize(n))
+        .filter(|n| !n.is_empty())
+        .collect::<Vec<String>>();
+    diesel::delete(project_tags::table.filter(project_tags::project_id.eq(pid))).execute(conn)?;
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let new_tags = names
+        .iter()
+        .map(|n| Tag {
+            tag_id: Uuid::new_v4().to_string(),
+            name: n.clone(),
+        })
+        .collect::<Vec<Tag>>();
+    diesel::insert_into(tags)
+        .values(new_tags)
+        .on_conflict(name)
+        .do_nothing()
+        .execute(conn)?;
+    let results = tags
+        .filter(name.eq_any(&names))
+        .order(name.asc())
+        .get_results::<Tag>(conn)?;
+
+    let links = results
+        .iter()
+        .map(|tag| ProjectTag {
+            project_id: pid.to_string(),
+            tag_id: tag.tag_id.clone(),
+        })
+        .collect::<Vec<ProjectTag>>();
+    diesel::insert_into(project_tags::table)
+        .values(links)
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+    Ok(results)
+}
@@ -0,0 +1,106 @@
+use chrono::{DateTime, Duration, Utc};
+use diesel::PgConnection;
+
+use super::{project_status_history, project_statuses, projects};
+use crate::{
+    errors::DbError,
+    models::{
+        project_status::ProjectStatus,
+        project_status_history::ProjectStatusHistory,
+        uptime::{ProjectUptime, StatusUptime, Uptime, MAX_WINDOW_DAYS},
+    },
+};
+
+pub fn find_by_project_status<'a>(
+    conn: &mut PgConnection,
+    id: &str,
+) -> Result<Option<StatusUptime>, DbError<'a>> {
+    let project_status = match project_statuses::find_by_id(conn, id)? {
+        Some(project_status) => project_status,
+        None => return Ok(None),
+    };
+    let mut uptimes = compute(conn, vec![project_status])?;
+    Ok(uptimes.pop())
+}
+
+pub fn find_by_project<'a>(
+    conn: &mut PgConnection,
+    id: &str,
+) -> Result<Option<ProjectUptime>, DbError<'a>> {
+    if projects::find_by_id(conn, id)?.is_none() {
+        return Ok(None);
+    }
+    let project_statuses = project_statuses::find_by_project(conn, id)?;
+    let status_uptimes = compute(conn, project_statuses)?;
+    let uptime = Uptime::average(
+        &status_uptimes
+            .iter()
+            .map(|s| s.uptime)
+            .collect::<Vec<Uptime>>(),
+    );
+    Ok(Some(ProjectUptime {
+        project_id: id.to_string(),
+        uptime,
+        project_statuses: status_uptimes,
+    }))
+}
+
+/// Uptime bars of each status, see `Uptime::daily`.
+pub fn find_daily<'a>(
+    conn: &mut PgConnection,
+    project_statuses: &[ProjectStatus],
+) -> Result<Vec<Vec<Option<f64>>>, DbError<'a>> {
+    let now = Utc::now();
+    let histories = find_histories(conn, project_statuses, now)?;
+    let bars = project_statuses
+        .iter()
+        .zip(histories)
+        .map(|(s, history)| Uptime::daily(s.health_state, &history, now))
+        .collect();
+    Ok(bars)
+}
+
+fn compute<'a>(
+    conn: &mut PgConnection,
+    project_statuses: Vec<ProjectStatus>,
+) -> Result<Vec<StatusUptime>, DbError<'a>> {
+    let now = Utc::now();
+    let histories = find_histories(conn, &project_statuses, now)?;
+
+    let uptimes = project_statuses
+        .into_iter()
+        .zip(histories)
+        .map(|(s, status_history)| StatusUptime {
+            uptime: Uptime::compute(s.health_state, &status_history, now),
+            project_status_id: s.project_status_id,
+            name: s.name,
+        })
+        .collect();
+    Ok(uptimes)
+}
+
+/// The last `MAX_WINDOW_DAYS` of history of each status.
+fn find_histories<'a>(
+    conn: &mut PgConnection,
+    project_statuses: &[ProjectStatus],
+    now: DateTime<Utc>,
+) -> Result<Vec<Vec<ProjectStatusHistory>>, DbError<'a>> {
+    let ids = project_statuses
+        .iter()
+        .map(|s| s.project_status_id.clone())
+        .collect::<Vec<String>>();
+    let history =
+        project_status_history::find_since(conn, &ids, now - Duration::days(MAX_WINDOW_DAYS))?;
+
+    let histories = project_statuses
+        .iter()
+        .map(|s| {
+            history
+                .iter()
+                .filter(|h| h.project_status_id == s.project_status_id)
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    Ok(histories)
+}
@@ -22,8 +22,20 @@ pub struct AppError<'a> {
 pub enum CustomError<'a> {
     #[error("'{0}' does not exist")]
     RecordDoesNotExist(&'a str),
+    #[error("'{0}' already exists")]
+    RecordAlreadyExists(&'a str),
     #[error("'{0}' already exists for project '{1}'")]
     ProjectStatusAlreadyExists(&'a str, &'a str),
+    #[error("'{0}' is not a valid RFC 3339 timestamp")]
+    InvalidTimestamp(&'a str),
+    #[error("'{0}' is not a valid cursor")]
+    InvalidCursor(&'a str),
+    #[error("'{0}' is not a PNG, JPEG, WebP or GIF image")]
+    UnsupportedMedia(&'a str),
+    #[error("Media must be at most {0} bytes")]
+    MediaTooLarge(u64),
+    #[error("Time range must be at most {0} days")]
+    RangeTooLong(i64),
 }
 
 impl<'a> AppError<'a> {
@@ -43,6 +55,10 @@ impl<'a> From<CustomError<'_>> for AppError<'a> {
                 Status::NotFound,
                 Cow::from(format!("'{0}' does not exist", record)),
             ),
+            CustomError::RecordAlreadyExists(record) => Self::new(
+                Status::Conflict,
+                Cow::from(format!("'{0}' already exists", record)),
+            ),
             CustomError::ProjectStatusAlreadyExists(status, project) => Self::new(
                 Status::Conflict,
                 Cow::from(format!(
@@ -50,6 +66,26 @@ impl<'a> From<CustomError<'_>> for AppError<'a> {
                     status, project
                 )),
             ),
+            CustomError::InvalidTimestamp(value) => Self::new(
+                Status::BadRequest,
+                Cow::from(format!("'{0}' is not a valid RFC 3339 timestamp", value)),
+            ),
+            CustomError::InvalidCursor(value) => Self::new(
+                Status::BadRequest,
+                Cow::from(format!("'{0}' is not a valid cursor", value)),
+            ),
+            CustomError::UnsupportedMedia(name) => Self::new(
+                Status::UnsupportedMediaType,
+                Cow::from(format!("'{0}' is not a PNG, JPEG, WebP or GIF image", name)),
+            ),
+            CustomError::MediaTooLarge(max_bytes) => Self::new(
+                Status::PayloadTooLarge,
+                Cow::from(format!("Media must be at most {0} bytes", max_bytes)),
+            ),
+            CustomError::RangeTooLong(max_days) => Self::new(
+                Status::BadRequest,
+                Cow::from(format!("Time range must be at most {0} days", max_days)),
+            ),
         }
     }
 }
@@ -78,6 +114,10 @@ impl<'a> From<DbError<'_>> for AppError<'a> {
                     info
                 )),
             ),
+            DbError::ForeignProjectStatus(psid) => Self::new(
+                Status::UnprocessableEntity,
+                Cow::from(format!("'{}' is not a status of the project", psid)),
+            ),
             _ => Self::default(),
         }
     }
@@ -110,6 +150,8 @@ pub enum DbError<'a> {
     RecordAlreadyExists(Cow<'a, str>),
     #[error("Foreign key value does not exist")]
     ForeignKeyDoesNotExist(Cow<'a, str>),
+    #[error("'{0}' is not a status of the project")]
+    ForeignProjectStatus(Cow<'a, str>),
     #[error("Internal db error")]
     InternalError,
 }
@@ -0,0 +1,41 @@
+/// Escapes `text` for HTML and XML content and attribute values.
+pub fn markup(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Escapes `text` for an iCalendar TEXT value.
+pub fn ical(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markup_escapes_every_special_character() {
+        assert_eq!(
+            markup("<a href=\"x\">Tom & Jerry's</a>"),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn markup_escapes_ampersands_once() {
+        assert_eq!(markup("&lt;"), "&amp;lt;");
+    }
+
+    #[test]
+    fn ical_escapes_text_values() {
+        assert_eq!(ical(r"a;b,c\d"), r"a\;b\,c\\d");
+        assert_eq!(ical("one\r\ntwo\nthree"), r"one\ntwo\nthree");
+    }
+}
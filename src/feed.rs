@@ -0,0 +1,148 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use rocket::http::RawStr;
+
+use crate::{escape, models::feed::FeedEntry};
+
+/// A feed of status transitions and incident updates, rendered as Atom or RSS.
+pub struct Feed<'a> {
+    pub title: String,
+    /// Absolute URL of the page the feed describes.
+    pub link: String,
+    /// Absolute URL the feed itself is served at, also used as the Atom feed id.
+    pub self_link: String,
+    pub public_url: &'a str,
+    pub entries: Vec<FeedEntry>,
+}
+
+impl<'a> Feed<'a> {
+    pub fn atom(&self) -> String {
+        let updated = self
+            .entries
+            .first()
+            .map_or_else(Utc::now, |entry| entry.published);
+        let mut xml = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+             <feed xmlns=\"http://www.w3.org/2005/Atom\">\n\
+             <id>{}</id>\n<title>{}</title>\n<updated>{}</updated>\n\
+             <link rel=\"self\" href=\"{}\"/>\n<link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>\n",
+            escape::markup(&self.self_link),
+            escape::markup(&self.title),
+            atom_date(updated),
+            escape::markup(&self.self_link),
+            escape::markup(&self.link)
+        );
+        for entry in &self.entries {
+            xml.push_str(&format!(
+                "<entry>\n<id>urn:uuid:{}</id>\n<title>{}</title>\n<updated>{}</updated>\n\
+                 <author><name>{}</name></author>\n<link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>\n\
+                 <summary>{}</summary>\n</entry>\n",
+                escape::markup(&entry.id),
+                escape::markup(&entry.title),
+                atom_date(entry.published),
+                escape::markup(&entry.project_name),
+                escape::markup(&self.entry_link(entry)),
+                escape::markup(&entry.summary)
+            ));
+        }
+        xml.push_str("</feed>\n");
+        xml
+    }
+
+    pub fn rss(&self) -> String {
+        let mut xml = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+             <rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\">\n<channel>\n\
+             <title>{}</title>\n<link>{}</link>\n<description>{}</description>\n\
+             <atom:link rel=\"self\" type=\"application/rss+xml\" href=\"{}\"/>\n",
+            escape::markup(&self.title),
+            escape::markup(&self.link),
+            escape::markup(&self.title),
+            escape::markup(&self.self_link)
+        );
+        if let Some(entry) = self.entries.first() {
+            xml.push_str(&format!(
+                "<lastBuildDate>{}</lastBuildDate>\n",
+                entry.published.to_rfc2822()
+            ));
+        }
+        for entry in &self.entries {
+            xml.push_str(&format!(
+                "<item>\n<guid isPermaLink=\"false\">urn:uuid:{}</guid>\n<title>{}</title>\n\
+                 <link>{}</link>\n<description>{}</description>\n<pubDate>{}</pubDate>\n</item>\n",
+                escape::markup(&entry.id),
+                escape::markup(&entry.title),
+                escape::markup(&self.entry_link(entry)),
+                escape::markup(&entry.summary),
+                entry.published.to_rfc2822()
+            ));
+        }
+        xml.push_str("</channel>\n</rss>\n");
+        xml
+    }
+
+    /// Entries link to the status page of their project.
+    fn entry_link(&self, entry: &FeedEntry) -> String {
+        status_page_url(self.public_url, Some(&entry.project_name))
+    }
+}
+
+/// Absolute URL of the global status page, or of the one of `project_name`.
+pub fn status_page_url(public_url: &str, project_name: Option<&str>) -> String {
+    match project_name {
+        Some(name) => format!(
+            "{}/status/{}",
+            public_url,
+            RawStr::new(name).percent_encode()
+        ),
+        None => format!("{}/status", public_url),
+    }
+}
+
+fn atom_date(date: DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn feed() -> Feed<'static> {
+        Feed {
+            title: String::from("<b>Tom & Jerry</b> status"),
+            link: status_page_url("https://example.com", Some("Tom & Jerry")),
+            self_link: String::from("https://example.com/feeds/atom.xml?a=1&b=2"),
+            public_url: "https://example.com",
+            entries: vec![FeedEntry {
+                id: String::from("entry"),
+                project_name: String::from("Tom & Jerry"),
+                title: String::from("api is <script>alert(1)</script>"),
+                summary: String::from("\"Down\" since 'now'"),
+                published: Utc.with_ymd_and_hms(2023, 5, 1, 12, 0, 0).unwrap(),
+            }],
+        }
+    }
+
+    #[test]
+    fn atom_escapes_text_and_attributes() {
+        let atom = feed().atom();
+        assert!(atom.contains("<title>&lt;b&gt;Tom &amp; Jerry&lt;/b&gt; status</title>"));
+        assert!(atom.contains("href=\"https://example.com/feeds/atom.xml?a=1&amp;b=2\""));
+        assert!(atom.contains("<title>api is &lt;script&gt;alert(1)&lt;/script&gt;</title>"));
+        assert!(atom.contains("<name>Tom &amp; Jerry</name>"));
+        assert!(atom.contains("<summary>&quot;Down&quot; since &#39;now&#39;</summary>"));
+        assert!(atom.contains("<updated>2023-05-01T12:00:00Z</updated>"));
+        assert!(!atom.contains("<script>"));
+    }
+
+    #[test]
+    fn rss_escapes_text() {
+        let rss = feed().rss();
+        assert!(rss.contains("<title>&lt;b&gt;Tom &amp; Jerry&lt;/b&gt; status</title>"));
+        assert!(rss.contains("<title>api is &lt;script&gt;alert(1)&lt;/script&gt;</title>"));
+        assert!(rss.contains("<description>&quot;Down&quot; since &#39;now&#39;</description>"));
+        assert!(rss.contains("<link>https://example.com/status/Tom%20%26%20Jerry</link>"));
+        assert!(!rss.contains("<script>"));
+    }
+}
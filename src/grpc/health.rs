@@ -0,0 +1,3 @@
+pub mod health_proto {
+    tonic::include_proto!("grpc.health.v1");
+}
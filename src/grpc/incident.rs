@@ -0,0 +1,312 @@
+use std::borrow::Cow;
+
+use chrono::Utc;
+use tonic::{Request, Response, Status};
+use uuid::Uuid;
+use validator::Validate;
+
+use self::incident_proto::{
+    incident_server::Incident,
+    {
+        CreateRequest, DeleteRequest, DeleteResponse, FindByIdRequest, FindRequest, FindResponse,
+        IncidentProto, TimelineEntryProto, UpdateRequest,
+    },
+};
+use super::{bearer_auth, timestamp_proto};
+use crate::{
+    db::{incidents, projects, Db},
+    errors::{CustomError, DbError},
+    jwt::JwtService,
+    models::{
+        incident::{
+            self, IncidentCreate, IncidentDetail, IncidentSeverity, IncidentState,
+            IncidentTimelineEntry, IncidentUpdate,
+        },
+        project_event::ProjectEvent,
+    },
+    reports::Reports,
+};
+
+pub mod incident_proto {
+    tonic::include_proto!("incident");
+}
+
+pub struct IncidentService {
+    db: Db,
+    reports: Reports,
+    jwt_service: JwtService,
+}
+
+impl IncidentService {
+    pub fn new(db: Db, reports: Reports, jwt_service: JwtService) -> Self {
+        Self {
+            db,
+            reports,
+            jwt_service,
+        }
+    }
+
+    /// Whether the request has a valid `authorization` bearer token, which unlocks the
+    /// incidents of draft, unlisted and archived projects.
+    fn is_authenticated<T>(&self, req: &Request<T>) -> bool {
+        bearer_auth(&self.jwt_service, req).is_some()
+    }
+}
+
+#[tonic::async_trait]
+impl Incident for IncidentService {
+    async fn find(&self, req: Request<FindRequest>) -> Result<Response<FindResponse>, Status> {
+        let authenticated = self.is_authenticated(&req);
+        let req = req.into_inner();
+        let found = self
+            .db
+            .run(move |conn| {
+                let found = match req.project_id {
+                    Some(pid) if projects::is_visible(conn, &pid, authenticated)? => {
+                        incidents::find_by_project(conn, &pid)?
+                    }
+                    Some(_) => Vec::new(),
+                    None => incidents::find(conn, !authenticated)?,
+                };
+                found
+                    .into_iter()
+                    .map(|i| incidents::find_detail(conn, &i.incident_id))
+                    .filter_map(Result::transpose)
+                    .collect::<Result<Vec<IncidentDetail>, _>>()
+            })
+            .await
+            .map_err(|_| Status::internal("Internal db error"))?;
+        let incidents = found.into_iter().map(detail_proto).collect();
+        Ok(Response::new(FindResponse { incidents }))
+    }
+
+    async fn find_by_id(
+        &self,
+        req: Request<FindByIdRequest>,
+    ) -> Result<Response<IncidentProto>, Status> {
+        let authenticated = self.is_authenticated(&req);
+        let req = req.into_inner();
+        let id_find = Cow::Owned(req.incident_id.clone());
+        let incident = self
+            .db
+            .run(move |conn| {
+                let incident = match incidents::find_detail(conn, &id_find)? {
+                    Some(incident) => incident,
+                    None => return Ok(None),
+                };
+                let pid = &incident.incident.project_id;
+                let visible = projects::is_visible(conn, pid, authenticated)?;
+                Ok::<_, DbError<'static>>(visible.then_some(incident))
+            })
+            .await
+            .map_err(|_| Status::internal("Internal db error"))?;
+        match incident {
+            Some(incident) => Ok(Response::new(detail_proto(incident))),
+            None => Err(Status::not_found(
+                CustomError::<'_>::RecordDoesNotExist(&req.incident_id).to_string(),
+            )),
+        }
+    }
+
+    async fn create(&self, req: Request<CreateRequest>) -> Result<Response<IncidentProto>, Status> {
+        let req = req.into_inner();
+        let severity = incident_proto::IncidentSeverity::from_i32(req.severity)
+            .map(severity_from_proto)
+            .ok_or_else(|| Status::invalid_argument("Invalid severity"))?;
+        let state = req
+            .state
+            .and_then(incident_proto::IncidentState::from_i32)
+            .map(state_from_proto);
+        IncidentCreate {
+            project_id: &req.project_id,
+            title: &req.title,
+            severity,
+            state,
+            message: &req.message,
+            project_status_ids: Vec::new(),
+        }
+        .validate()
+        .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        let now = Utc::now();
+        let mut new_incident = incident::Incident {
+            incident_id: Uuid::new_v4().to_string(),
+            project_id: req.project_id,
+            title: req.title,
+            severity,
+            state: Default::default(),
+            automatic: false,
+            created_at: now,
+            resolved_at: None,
+        };
+        new_incident.set_state(state.unwrap_or_default(), now);
+        let entry = IncidentTimelineEntry::new(&new_incident, req.message, now);
+        let psids = req.project_status_ids;
+        let incident = self
+            .db
+            .run(move |conn| incidents::create(conn, new_incident, entry, &psids))
+            .await
+            .map_err(|e| match e {
+                DbError::ForeignProjectStatus(_) => Status::invalid_argument(e.to_string()),
+                _ => Status::internal("Incident creation failed"),
+            })?;
+
+        self.reports
+            .publish(ProjectEvent::Incident(incident.clone()));
+        Ok(Response::new(detail_proto(incident)))
+    }
+
+    async fn update(&self, req: Request<UpdateRequest>) -> Result<Response<IncidentProto>, Status> {
+        let req = req.into_inner();
+        let severity = req
+            .severity
+            .and_then(incident_proto::IncidentSeverity::from_i32)
+            .map(severity_from_proto);
+        let state = req
+            .state
+            .and_then(incident_proto::IncidentState::from_i32)
+            .map(state_from_proto);
+        IncidentUpdate {
+            title: req.title.as_deref(),
+            severity,
+            state,
+            message: req.message.as_deref(),
+            project_status_ids: None,
+        }
+        .validate()
+        .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        let id_find = Cow::Owned(req.incident_id.clone());
+        let id_update = Cow::Owned(req.incident_id.clone());
+        let existing_incident = self
+            .db
+            .run(move |conn| incidents::find_by_id(conn, &id_find))
+            .await
+            .map_err(|_| Status::internal("Internal db error"))?;
+        let mut updated_incident = match existing_incident {
+            Some(incident) => incident,
+            None => {
+                return Err(Status::not_found(
+                    CustomError::<'_>::RecordDoesNotExist(&req.incident_id).to_string(),
+                ))
+            }
+        };
+
+        let now = Utc::now();
+        if let Some(new_title) = req.title {
+            updated_incident.title = new_title;
+        }
+        if let Some(new_severity) = severity {
+            updated_incident.severity = new_severity;
+        }
+        if let Some(new_state) = state {
+            updated_incident.set_state(new_state, now);
+        }
+        let entry = match (req.message, state) {
+            (Some(message), _) => Some(message),
+            (None, Some(new_state)) => Some(format!("Marked as {}", new_state.label())),
+            (None, None) => None,
+        }
+        .map(|message| IncidentTimelineEntry::new(&updated_incident, message, now));
+        let psids = req.project_status_ids.map(|ids| ids.project_status_ids);
+        let incident = self
+            .db
+            .run(move |conn| {
+                incidents::update(conn, &id_update, updated_incident, entry, psids.as_deref())
+            })
+            .await
+            .map_err(|e| match e {
+                DbError::ForeignProjectStatus(_) => Status::invalid_argument(e.to_string()),
+                _ => Status::internal("Incident update failed"),
+            })?;
+
+        self.reports
+            .publish(ProjectEvent::Incident(incident.clone()));
+        Ok(Response::new(detail_proto(incident)))
+    }
+
+    async fn delete(
+        &self,
+        req: Request<DeleteRequest>,
+    ) -> Result<Response<DeleteResponse>, Status> {
+        let req = req.into_inner();
+        let id_find = Cow::Owned(req.incident_id.clone());
+        let id_delete = Cow::Owned(req.incident_id.clone());
+        if self
+            .db
+            .run(move |conn| incidents::find_by_id(conn, &id_find))
+            .await
+            .map_err(|_| Status::internal("Internal db error"))?
+            .is_none()
+        {
+            return Err(Status::not_found(
+                CustomError::<'_>::RecordDoesNotExist(&req.incident_id).to_string(),
+            ));
+        }
+
+        self.db
+            .run(move |conn| incidents::delete(conn, &id_delete))
+            .await
+            .map_err(|_| Status::internal("Incident deletion failed"))?;
+        Ok(Response::new(DeleteResponse {}))
+    }
+}
+
+fn detail_proto(detail: IncidentDetail) -> IncidentProto {
+    let i = detail.incident;
+    IncidentProto {
+        incident_id: i.incident_id,
+        project_id: i.project_id,
+        title: i.title,
+        severity: severity_proto(i.severity) as i32,
+        state: state_proto(i.state) as i32,
+        automatic: i.automatic,
+        created_at: Some(timestamp_proto(i.created_at)),
+        resolved_at: i.resolved_at.map(timestamp_proto),
+        timeline: detail
+            .timeline
+            .into_iter()
+            .map(|e| TimelineEntryProto {
+                incident_timeline_id: e.incident_timeline_id,
+                state: state_proto(e.state) as i32,
+                message: e.message,
+                created_at: Some(timestamp_proto(e.created_at)),
+            })
+            .collect(),
+        project_status_ids: detail.project_status_ids,
+    }
+}
+
+fn severity_proto(severity: IncidentSeverity) -> incident_proto::IncidentSeverity {
+    match severity {
+        IncidentSeverity::Minor => incident_proto::IncidentSeverity::Minor,
+        IncidentSeverity::Major => incident_proto::IncidentSeverity::Major,
+        IncidentSeverity::Critical => incident_proto::IncidentSeverity::Critical,
+    }
+}
+
+fn severity_from_proto(severity: incident_proto::IncidentSeverity) -> IncidentSeverity {
+    match severity {
+        incident_proto::IncidentSeverity::Minor => IncidentSeverity::Minor,
+        incident_proto::IncidentSeverity::Major => IncidentSeverity::Major,
+        incident_proto::IncidentSeverity::Critical => IncidentSeverity::Critical,
+    }
+}
+
+fn state_proto(state: IncidentState) -> incident_proto::IncidentState {
+    match state {
+        IncidentState::Investigating => incident_proto::IncidentState::Investigating,
+        IncidentState::Identified => incident_proto::IncidentState::Identified,
+        IncidentState::Monitoring => incident_proto::IncidentState::Monitoring,
+        IncidentState::Resolved => incident_proto::IncidentState::Resolved,
+    }
+}
+
+fn state_from_proto(state: incident_proto::IncidentState) -> IncidentState {
+    match state {
+        incident_proto::IncidentState::Investigating => IncidentState::Investigating,
+        incident_proto::IncidentState::Identified => IncidentState::Identified,
+        incident_proto::IncidentState::Monitoring => IncidentState::Monitoring,
+        incident_proto::IncidentState::Resolved => IncidentState::Resolved,
+    }
+}
@@ -1,2 +1,86 @@
+use chrono::{DateTime, Utc};
+use tonic::{Request, Status};
+
+use crate::{
+    jwt::JwtService,
+    models::{health_state::HealthState, sort::Sort, uptime::Uptime},
+    pagination::PageRequest,
+    request_guards::bearer_auth::BearerAuth,
+};
+
+pub mod health;
+pub mod incident;
 pub mod project;
 pub mod project_status;
+
+/// Types shared by the project and project status services.
+pub mod common_proto {
+    tonic::include_proto!("common");
+}
+
+fn timestamp_proto(date: DateTime<Utc>) -> prost_types::Timestamp {
+    prost_types::Timestamp {
+        seconds: date.timestamp(),
+        nanos: date.timestamp_subsec_nanos() as i32,
+    }
+}
+
+/// The request's `authorization` bearer token, if it has a valid one.
+fn bearer_auth<'j, T>(jwt_service: &'j JwtService, req: &Request<T>) -> Option<BearerAuth<'j>> {
+    req.metadata()
+        .get("authorization")
+        .and_then(|header| header.to_str().ok())
+        .and_then(|header| BearerAuth::new(header, jwt_service).ok())
+}
+
+/// The page asked for by the `limit` and `cursor` of a find request, where zero and
+/// empty mean unset.
+fn page_request(limit: u32, cursor: &str) -> Result<PageRequest, Status> {
+    PageRequest::new(
+        (limit > 0).then_some(limit),
+        (!cursor.is_empty()).then_some(cursor),
+    )
+    .map_err(|e| Status::invalid_argument(e.to_string()))
+}
+
+fn sort_from_proto(sort: common_proto::Sort) -> Sort {
+    match sort {
+        common_proto::Sort::CreatedAt => Sort::CreatedAt,
+        common_proto::Sort::CreatedAtDesc => Sort::CreatedAtDesc,
+        common_proto::Sort::UpdatedAt => Sort::UpdatedAt,
+        common_proto::Sort::UpdatedAtDesc => Sort::UpdatedAtDesc,
+        common_proto::Sort::Name => Sort::Name,
+        common_proto::Sort::NameDesc => Sort::NameDesc,
+    }
+}
+
+fn health_state_proto(health_state: HealthState) -> common_proto::HealthState {
+    match health_state {
+        HealthState::Operational => common_proto::HealthState::Operational,
+        HealthState::Degraded => common_proto::HealthState::Degraded,
+        HealthState::PartialOutage => common_proto::HealthState::PartialOutage,
+        HealthState::MajorOutage => common_proto::HealthState::MajorOutage,
+        HealthState::Maintenance => common_proto::HealthState::Maintenance,
+        HealthState::Unknown => common_proto::HealthState::Unknown,
+    }
+}
+
+fn health_state_from_proto(health_state: common_proto::HealthState) -> HealthState {
+    match health_state {
+        common_proto::HealthState::Operational => HealthState::Operational,
+        common_proto::HealthState::Degraded => HealthState::Degraded,
+        common_proto::HealthState::PartialOutage => HealthState::PartialOutage,
+        common_proto::HealthState::MajorOutage => HealthState::MajorOutage,
+        common_proto::HealthState::Maintenance => HealthState::Maintenance,
+        common_proto::HealthState::Unknown => HealthState::Unknown,
+    }
+}
+
+fn uptime_proto(uptime: Uptime) -> common_proto::UptimeProto {
+    common_proto::UptimeProto {
+        last_24h: uptime.last_24h,
+        last_7d: uptime.last_7d,
+        last_30d: uptime.last_30d,
+        last_90d: uptime.last_90d,
+    }
+}
@@ -1,10 +1,31 @@
+use std::borrow::Cow;
+
 use tonic::{Request, Response, Status};
 
 use self::project_proto::{
     project_server::Project,
-    {FindRequest, FindResponse, ProjectProto},
+    {
+        FindRequest, FindResponse, ProjectLinkProto, ProjectMediaProto, ProjectProto,
+        ProjectStatusSummaryProto, SearchRequest, SearchResponse, SearchResultProto,
+        StatusUptimeProto, UptimeRequest, UptimeResponse,
+    },
+};
+use super::{
+    bearer_auth, common_proto, health_state_proto, page_request, sort_from_proto, timestamp_proto,
+    uptime_proto,
+};
+use crate::{
+    db::{projects, uptime, Db},
+    errors::CustomError,
+    jwt::JwtService,
+    models::{
+        project::{ProjectDetail, ProjectFilter},
+        project_link::{LinkKind, ProjectLink},
+        project_media::{MediaKind, ProjectMedia},
+        rollup::{ProjectHealth, RollupPolicy},
+        visibility::Visibility,
+    },
 };
-use crate::db::{projects, Db};
 
 pub mod project_proto {
     tonic::include_proto!("project");
@@ -12,28 +33,240 @@ pub mod project_proto {
 
 pub struct ProjectService {
     db: Db,
+    jwt_service: JwtService,
 }
 
 impl ProjectService {
-    pub fn new(db: Db) -> Self {
-        Self { db }
+    pub fn new(db: Db, jwt_service: JwtService) -> Self {
+        Self { db, jwt_service }
+    }
+
+    /// Whether the request has a valid `authorization` bearer token, which unlocks
+    /// draft, unlisted and archived projects.
+    fn is_authenticated<T>(&self, req: &Request<T>) -> bool {
+        bearer_auth(&self.jwt_service, req).is_some()
     }
 }
 
 #[tonic::async_trait]
 impl Project for ProjectService {
-    async fn find(&self, _req: Request<FindRequest>) -> Result<Response<FindResponse>, Status> {
-        let projects = self.db.run(projects::find).await.unwrap();
+    async fn find(&self, req: Request<FindRequest>) -> Result<Response<FindResponse>, Status> {
+        let authenticated = self.is_authenticated(&req);
+        let req = req.into_inner();
+        let sort = req
+            .sort
+            .and_then(common_proto::Sort::from_i32)
+            .map(sort_from_proto);
+        let page = page_request(req.limit, &req.cursor)?;
+        let filter = ProjectFilter {
+            name: req.name,
+            tag: req.tag,
+            featured: req.featured,
+            visibility: if authenticated {
+                req.visibility
+                    .and_then(project_proto::Visibility::from_i32)
+                    .map(visibility_from_proto)
+            } else {
+                Some(Visibility::Published)
+            },
+        };
+        let (with_health, with_statuses) = (req.include_health, req.include_statuses);
+        let projects = self
+            .db
+            .run(move |conn| {
+                projects::find_details(conn, &filter, sort, page, with_health, with_statuses)
+            })
+            .await
+            .map_err(|_| Status::internal("Internal db error"))?;
+        let next_cursor = projects.next_cursor.unwrap_or_default();
         let projects = projects
+            .items
             .into_iter()
-            .map(|p| ProjectProto {
-                project_id: p.project_id,
-                name: p.name,
-                description: p.description.unwrap_or("".to_owned()),
-                url: p.url,
-                github_repository: p.github_repository,
-            })
+            .map(project_proto)
             .collect::<Vec<ProjectProto>>();
-        Ok(Response::new(FindResponse { projects }))
+        Ok(Response::new(FindResponse {
+            projects,
+            next_cursor,
+        }))
+    }
+
+    async fn uptime(
+        &self,
+        req: Request<UptimeRequest>,
+    ) -> Result<Response<UptimeResponse>, Status> {
+        let authenticated = self.is_authenticated(&req);
+        let req = req.into_inner();
+        let id_find = Cow::Owned(req.project_id.clone());
+        let project_uptime = self
+            .db
+            .run(move |conn| {
+                if !projects::is_visible(conn, &id_find, authenticated)? {
+                    return Ok(None);
+                }
+                uptime::find_by_project(conn, &id_find)
+            })
+            .await
+            .map_err(|_| Status::internal("Internal db error"))?;
+        let project_uptime = match project_uptime {
+            Some(project_uptime) => project_uptime,
+            None => {
+                return Err(Status::not_found(
+                    CustomError::<'_>::RecordDoesNotExist(&req.project_id).to_string(),
+                ))
+            }
+        };
+
+        Ok(Response::new(UptimeResponse {
+            project_id: project_uptime.project_id,
+            uptime: Some(uptime_proto(project_uptime.uptime)),
+            project_statuses: project_uptime
+                .project_statuses
+                .into_iter()
+                .map(|s| StatusUptimeProto {
+                    project_status_id: s.project_status_id,
+                    name: s.name,
+                    uptime: Some(uptime_proto(s.uptime)),
+                })
+                .collect(),
+        }))
+    }
+
+    async fn search(
+        &self,
+        req: Request<SearchRequest>,
+    ) -> Result<Response<SearchResponse>, Status> {
+        let published_only = !self.is_authenticated(&req);
+        let req = req.into_inner();
+        let page = page_request(req.limit, &req.cursor)?;
+        let results = self
+            .db
+            .run(move |conn| projects::search_details(conn, &req.q, published_only, page))
+            .await
+            .map_err(|_| Status::internal("Internal db error"))?;
+
+        Ok(Response::new(SearchResponse {
+            next_cursor: results.next_cursor.unwrap_or_default(),
+            results: results
+                .items
+                .into_iter()
+                .map(|r| SearchResultProto {
+                    project: Some(project_proto(r.detail)),
+                    rank: r.rank,
+                    name_highlight: r.name_highlight,
+                    description_highlight: r.description_highlight,
+                })
+                .collect(),
+        }))
+    }
+}
+
+fn project_proto(detail: ProjectDetail) -> ProjectProto {
+    let p = detail.project;
+    ProjectProto {
+        project_id: p.project_id,
+        name: p.name,
+        description: p.description.unwrap_or("".to_owned()),
+        description_html: p.description_html.unwrap_or_default(),
+        url: p.url,
+        github_repository: p.github_repository,
+        slug: p.slug,
+        featured: p.featured,
+        position: p.position,
+        visibility: visibility_proto(p.visibility) as i32,
+        tags: detail.tags,
+        links: detail.links.into_iter().map(project_link_proto).collect(),
+        media: detail.media.into_iter().map(project_media_proto).collect(),
+        rollup_policy: rollup_policy_proto(p.rollup_policy) as i32,
+        health: detail.health.map(|h| project_health_proto(h) as i32),
+        project_statuses: detail
+            .project_statuses
+            .unwrap_or_default()
+            .into_iter()
+            .map(|s| ProjectStatusSummaryProto {
+                project_status_id: s.project_status_id,
+                name: s.name,
+                is_healthy: s.is_healthy,
+                health_state: health_state_proto(s.health_state) as i32,
+            })
+            .collect(),
+        created_at: Some(timestamp_proto(p.created_at)),
+        updated_at: Some(timestamp_proto(p.updated_at)),
+    }
+}
+
+fn visibility_proto(visibility: Visibility) -> project_proto::Visibility {
+    match visibility {
+        Visibility::Draft => project_proto::Visibility::Draft,
+        Visibility::Unlisted => project_proto::Visibility::Unlisted,
+        Visibility::Published => project_proto::Visibility::Published,
+        Visibility::Archived => project_proto::Visibility::Archived,
+    }
+}
+
+fn visibility_from_proto(visibility: project_proto::Visibility) -> Visibility {
+    match visibility {
+        project_proto::Visibility::Draft => Visibility::Draft,
+        project_proto::Visibility::Unlisted => Visibility::Unlisted,
+        project_proto::Visibility::Published => Visibility::Published,
+        project_proto::Visibility::Archived => Visibility::Archived,
+    }
+}
+
+fn project_link_proto(link: ProjectLink) -> ProjectLinkProto {
+    ProjectLinkProto {
+        project_link_id: link.project_link_id,
+        kind: link_kind_proto(link.kind) as i32,
+        url: link.url,
+        label: link.label,
+    }
+}
+
+fn link_kind_proto(kind: LinkKind) -> project_proto::LinkKind {
+    match kind {
+        LinkKind::Homepage => project_proto::LinkKind::Homepage,
+        LinkKind::Repository => project_proto::LinkKind::Repository,
+        LinkKind::Docs => project_proto::LinkKind::Docs,
+        LinkKind::Demo => project_proto::LinkKind::Demo,
+        LinkKind::CratesIo => project_proto::LinkKind::CratesIo,
+        LinkKind::Npm => project_proto::LinkKind::Npm,
+        LinkKind::Pypi => project_proto::LinkKind::Pypi,
+        LinkKind::Other => project_proto::LinkKind::Other,
+    }
+}
+
+fn project_media_proto(media: ProjectMedia) -> ProjectMediaProto {
+    ProjectMediaProto {
+        project_media_id: media.project_media_id,
+        kind: media_kind_proto(media.kind) as i32,
+        content_type: media.content_type,
+        width: media.width,
+        height: media.height,
+        caption: media.caption,
+        url: media.url,
+        thumbnail_url: media.thumbnail_url,
+    }
+}
+
+fn media_kind_proto(kind: MediaKind) -> project_proto::MediaKind {
+    match kind {
+        MediaKind::Screenshot => project_proto::MediaKind::Screenshot,
+        MediaKind::Cover => project_proto::MediaKind::Cover,
+    }
+}
+
+fn rollup_policy_proto(rollup_policy: RollupPolicy) -> project_proto::RollupPolicy {
+    match rollup_policy {
+        RollupPolicy::Worst => project_proto::RollupPolicy::Worst,
+        RollupPolicy::Majority => project_proto::RollupPolicy::Majority,
+        RollupPolicy::Best => project_proto::RollupPolicy::Best,
+    }
+}
+
+fn project_health_proto(health: ProjectHealth) -> project_proto::ProjectHealth {
+    match health {
+        ProjectHealth::Healthy => project_proto::ProjectHealth::Healthy,
+        ProjectHealth::Degraded => project_proto::ProjectHealth::Degraded,
+        ProjectHealth::Down => project_proto::ProjectHealth::Down,
+        ProjectHealth::Unknown => project_proto::ProjectHealth::Unknown,
     }
 }
@@ -4,11 +4,26 @@ use tonic::{Request, Response, Status};
 
 use self::project_status_proto::{
     project_status_server::ProjectStatus,
-    {FindRequest, FindResponse, ProjectStatusProto, UpdateRequest, UpdateResponse},
+    {
+        CheckProto, FindRequest, FindResponse, ProjectStatusProto, UpdateRequest, UpdateResponse,
+        UptimeRequest, UptimeResponse,
+    },
+};
+use super::{
+    bearer_auth, common_proto, health_state_from_proto, health_state_proto, page_request,
+    sort_from_proto, timestamp_proto, uptime_proto,
 };
 use crate::{
-    db::{project_statuses, Db},
+    db::{project_statuses, projects, uptime, Db},
     errors::CustomError,
+    jwt::JwtService,
+    models::{
+        health_state::HealthState,
+        project_status::{self, CheckKind, ProjectStatusFilter},
+        project_status_history::ReportSource,
+        sort::Sort,
+    },
+    reports::{Measurement, Report, Reporter, Reports},
 };
 
 pub mod project_status_proto {
@@ -17,37 +32,96 @@ pub mod project_status_proto {
 
 pub struct ProjectStatusService {
     db: Db,
+    reports: Reports,
+    jwt_service: JwtService,
 }
 
 impl ProjectStatusService {
-    pub fn new(db: Db) -> Self {
-        Self { db }
+    pub fn new(db: Db, reports: Reports, jwt_service: JwtService) -> Self {
+        Self {
+            db,
+            reports,
+            jwt_service,
+        }
+    }
+
+    /// Takes the reporter from an optional `authorization` bearer token.
+    fn reporter<T>(&self, req: &Request<T>) -> Reporter {
+        let sub = bearer_auth(&self.jwt_service, req).map(|auth| auth.claims.sub);
+        Reporter::new(ReportSource::Grpc, sub)
+    }
+
+    /// Whether the request has a valid `authorization` bearer token, which unlocks the
+    /// statuses of draft, unlisted and archived projects.
+    fn is_authenticated<T>(&self, req: &Request<T>) -> bool {
+        bearer_auth(&self.jwt_service, req).is_some()
     }
 }
 
 #[tonic::async_trait]
 impl ProjectStatus for ProjectStatusService {
-    async fn find(&self, _req: Request<FindRequest>) -> Result<Response<FindResponse>, Status> {
-        let project_statuses = self.db.run(project_statuses::find).await.unwrap();
+    async fn find(&self, req: Request<FindRequest>) -> Result<Response<FindResponse>, Status> {
+        let published_only = !self.is_authenticated(&req);
+        let req = req.into_inner();
+        let sort = common_proto::Sort::from_i32(req.sort).map_or(Sort::default(), sort_from_proto);
+        let page = page_request(req.limit, &req.cursor)?;
+        let filter = ProjectStatusFilter {
+            project_id: req.project_id,
+            is_healthy: req.is_healthy,
+            health_state: req
+                .health_state
+                .and_then(common_proto::HealthState::from_i32)
+                .map(health_state_from_proto),
+            check_enabled: req.check_enabled,
+        };
+        let project_statuses = self
+            .db
+            .run(move |conn| project_statuses::find(conn, &filter, sort, published_only, page))
+            .await
+            .map_err(|_| Status::internal("Internal db error"))?;
+        let next_cursor = project_statuses.next_cursor.unwrap_or_default();
         let project_statuses = project_statuses
+            .items
             .into_iter()
             .map(|p| ProjectStatusProto {
+                check: Some(check_proto(&p)),
                 project_status_id: p.project_status_id,
                 name: p.name,
                 is_healthy: p.is_healthy,
                 project_id: p.project_id,
+                flapping: p.flapping,
+                failure_threshold: p.failure_threshold,
+                success_threshold: p.success_threshold,
+                health_state: health_state_proto(p.health_state) as i32,
+                created_at: Some(timestamp_proto(p.created_at)),
+                updated_at: Some(timestamp_proto(p.updated_at)),
             })
             .collect::<Vec<ProjectStatusProto>>();
-        Ok(Response::new(FindResponse { project_statuses }))
+        Ok(Response::new(FindResponse {
+            project_statuses,
+            next_cursor,
+        }))
     }
 
     async fn update(
         &self,
         req: Request<UpdateRequest>,
     ) -> Result<Response<UpdateResponse>, Status> {
+        let reporter = self.reporter(&req);
         let req = req.into_inner();
+        if req.latency_ms.map_or(false, |latency_ms| latency_ms < 0) {
+            return Err(Status::invalid_argument("Latency must not be negative"));
+        }
+        if req
+            .detail
+            .as_ref()
+            .map_or(false, |detail| detail.chars().count() > 1024)
+        {
+            return Err(Status::invalid_argument(
+                "Detail must be at most 1024 characters",
+            ));
+        }
         let id_find = Cow::Owned(req.project_status_id.clone());
-        let id_update = Cow::Owned(req.project_status_id.clone());
         let existing_project_status = self
             .db
             .run(move |conn| project_statuses::find_by_id(conn, &id_find))
@@ -62,18 +136,96 @@ impl ProjectStatus for ProjectStatusService {
             }
         };
 
-        let mut updated_project_status = existing_project_status;
-        updated_project_status.is_healthy = req.is_healthy;
+        let health_state = req
+            .health_state
+            .and_then(common_proto::HealthState::from_i32)
+            .map_or(HealthState::from(req.is_healthy), health_state_from_proto);
+        let measurement = Measurement {
+            latency_ms: req.latency_ms,
+            detail: req.detail,
+        };
         let updated_project_status = self
-            .db
-            .run(move |conn| project_statuses::update(conn, &id_update, updated_project_status))
+            .reports
+            .save(
+                &self.db,
+                &existing_project_status.project_status_id,
+                None,
+                Some(Report::Health(health_state)),
+                reporter,
+                measurement,
+            )
             .await
             .map_err(|_| Status::internal("Project status update failed"))?;
+
         Ok(Response::new(UpdateResponse {
+            check: Some(check_proto(&updated_project_status)),
             project_status_id: updated_project_status.project_status_id,
             name: updated_project_status.name,
             is_healthy: updated_project_status.is_healthy,
             project_id: updated_project_status.project_id,
+            flapping: updated_project_status.flapping,
+            failure_threshold: updated_project_status.failure_threshold,
+            success_threshold: updated_project_status.success_threshold,
+            health_state: health_state_proto(updated_project_status.health_state) as i32,
+            created_at: Some(timestamp_proto(updated_project_status.created_at)),
+            updated_at: Some(timestamp_proto(updated_project_status.updated_at)),
+        }))
+    }
+
+    async fn uptime(
+        &self,
+        req: Request<UptimeRequest>,
+    ) -> Result<Response<UptimeResponse>, Status> {
+        let authenticated = self.is_authenticated(&req);
+        let req = req.into_inner();
+        let id_find = Cow::Owned(req.project_status_id.clone());
+        let status_uptime = self
+            .db
+            .run(move |conn| {
+                let visible = match project_statuses::find_by_id(conn, &id_find)? {
+                    Some(p) => projects::is_visible(conn, &p.project_id, authenticated)?,
+                    None => false,
+                };
+                if !visible {
+                    return Ok(None);
+                }
+                uptime::find_by_project_status(conn, &id_find)
+            })
+            .await
+            .map_err(|_| Status::internal("Internal db error"))?;
+        let status_uptime = match status_uptime {
+            Some(status_uptime) => status_uptime,
+            None => {
+                return Err(Status::not_found(
+                    CustomError::<'_>::RecordDoesNotExist(&req.project_status_id).to_string(),
+                ))
+            }
+        };
+
+        Ok(Response::new(UptimeResponse {
+            project_status_id: status_uptime.project_status_id,
+            name: status_uptime.name,
+            uptime: Some(uptime_proto(status_uptime.uptime)),
         }))
     }
 }
+
+fn check_proto(p: &project_status::ProjectStatus) -> CheckProto {
+    let kind = match p.check_kind {
+        CheckKind::Http => project_status_proto::CheckKind::Http,
+        CheckKind::Tcp => project_status_proto::CheckKind::Tcp,
+        CheckKind::GrpcHealth => project_status_proto::CheckKind::GrpcHealth,
+        CheckKind::Keyword => project_status_proto::CheckKind::Keyword,
+    };
+    CheckProto {
+        enabled: p.check_enabled,
+        kind: kind as i32,
+        target: p.check_target.clone().unwrap_or_default(),
+        interval_secs: p.check_interval_secs.unwrap_or_default(),
+        timeout_ms: p.check_timeout_ms.unwrap_or_default(),
+        expected_status: p.check_expected_status.unwrap_or_default(),
+        expected_body: p.check_expected_body.clone().unwrap_or_default(),
+        max_latency_ms: p.check_max_latency_ms.unwrap_or_default(),
+        grpc_service: p.check_grpc_service.clone().unwrap_or_default(),
+    }
+}
@@ -12,6 +12,7 @@ pub struct JwtClaims<'a> {
     pub iat: i64,
 }
 
+#[derive(Clone)]
 pub struct JwtService(String);
 
 impl JwtService {
@@ -4,62 +4,182 @@ extern crate rocket;
 use db::Db;
 use dotenv::dotenv;
 use rocket::fairing::AdHoc;
-use rocket::tokio::sync::broadcast::channel;
+use rocket::fs::FileServer;
+use rocket::tokio::sync::broadcast::{channel, Sender};
+use std::sync::Arc;
 use tonic::transport::Server;
 
-use crate::routes::{health, project_events, project_statuses, projects};
+use crate::config::AppConfig;
+use crate::routes::{
+    badges, feeds, health, incidents, maintenance_windows, project_events, project_links,
+    project_media, project_recipients, project_statuses, projects, status_page, tags, trash,
+};
 use grpc::{
+    incident::{incident_proto::incident_server::IncidentServer, IncidentService},
     project::{project_proto::project_server::ProjectServer, ProjectService},
     project_status::{
         project_status_proto::project_status_server::ProjectStatusServer, ProjectStatusService,
     },
 };
-use models::project_status::ProjectStatus;
+use maintenance::MaintenanceScheduler;
+use media::{LocalStorage, Storage, FORM_OVERHEAD_BYTES};
+use models::project_event::ProjectEvent;
+use prober::Prober;
+use published::PublishedProjects;
+use purge::Purger;
+use reports::Reports;
 
+mod badge;
+mod calendar;
 mod config;
 mod db;
 mod errors;
+mod escape;
+mod feed;
 mod grpc;
 mod jwt;
+mod maintenance;
+mod markdown;
+mod media;
 mod models;
+mod notifier;
+mod pagination;
+mod prober;
+mod published;
+mod purge;
+mod reports;
 mod request_guards;
 mod routes;
 mod schema;
+mod status_html;
 
 #[launch]
 pub fn rocket() -> _ {
     dotenv().ok();
-    rocket::build()
+    let media_max_bytes = AppConfig::media_max_bytes();
+    let figment = rocket::Config::figment()
+        .merge(("limits.file", media_max_bytes))
+        .merge(("limits.data-form", media_max_bytes + FORM_OVERHEAD_BYTES));
+    rocket::custom(figment)
         .attach(db::Db::fairing())
         .attach(config::AppConfig::manage())
+        .attach(AdHoc::on_ignite("Reports", |rocket| async move {
+            let notifier = rocket.state::<AppConfig>().unwrap().notifier.clone();
+            let events = rocket.state::<Sender<ProjectEvent>>().unwrap().clone();
+            rocket.manage(Reports::new(notifier, events))
+        }))
+        .attach(AdHoc::on_ignite(
+            "Published projects",
+            |rocket| async move {
+                let pool = Db::pool(&rocket).unwrap().clone();
+                rocket.manage(PublishedProjects::new(pool))
+            },
+        ))
+        .attach(AdHoc::on_ignite("Media", |rocket| async move {
+            let app_config = rocket.state::<AppConfig>().unwrap();
+            let dir = app_config.media.dir.clone();
+            std::fs::create_dir_all(&dir).expect("Unable to create the media directory");
+            let storage: Arc<dyn Storage> = Arc::new(LocalStorage::new(
+                dir.clone(),
+                format!("{}/media", app_config.public_url),
+            ));
+            rocket
+                .manage(storage)
+                .mount("/media", FileServer::from(dir))
+        }))
         .attach(AdHoc::on_liftoff("gRPC", |rocket| {
             Box::pin(async move {
                 let addr = "[::1]:9000".parse().unwrap();
 
                 let project_db = Db::get_one(rocket).await.unwrap();
                 let project_status_db = Db::get_one(rocket).await.unwrap();
-                let project_service = ProjectService::new(project_db);
-                let project_status_service = ProjectStatusService::new(project_status_db);
+                let incident_db = Db::get_one(rocket).await.unwrap();
+                let app_config = rocket.state::<AppConfig>().unwrap();
+                let project_service =
+                    ProjectService::new(project_db, app_config.jwt_service.clone());
+                let reports = rocket.state::<Reports>().unwrap();
+                let project_status_service = ProjectStatusService::new(
+                    project_status_db,
+                    reports.clone(),
+                    app_config.jwt_service.clone(),
+                );
+                let incident_service = IncidentService::new(
+                    incident_db,
+                    reports.clone(),
+                    app_config.jwt_service.clone(),
+                );
 
                 let server = Server::builder()
                     .add_service(ProjectServer::new(project_service))
                     .add_service(ProjectStatusServer::new(project_status_service))
+                    .add_service(IncidentServer::new(incident_service))
                     .serve(addr);
 
                 tokio::spawn(server);
             })
         }))
-        .manage(channel::<ProjectStatus>(1024).0)
+        .attach(AdHoc::on_liftoff("Prober", |rocket| {
+            Box::pin(async move {
+                let app_config = rocket.state::<AppConfig>().unwrap();
+                let prober_db = Db::get_one(rocket).await.unwrap();
+                let prober = Prober::new(
+                    prober_db,
+                    rocket.state::<Reports>().unwrap().clone(),
+                    &app_config.prober,
+                );
+
+                tokio::spawn(prober.run());
+            })
+        }))
+        .attach(AdHoc::on_liftoff("Maintenance", |rocket| {
+            Box::pin(async move {
+                let maintenance_db = Db::get_one(rocket).await.unwrap();
+                let scheduler = MaintenanceScheduler::new(
+                    maintenance_db,
+                    rocket.state::<Reports>().unwrap().clone(),
+                );
+
+                tokio::spawn(scheduler.run());
+            })
+        }))
+        .attach(AdHoc::on_liftoff("Purge", |rocket| {
+            Box::pin(async move {
+                let app_config = rocket.state::<AppConfig>().unwrap();
+                let purge_db = Db::get_one(rocket).await.unwrap();
+                let storage = rocket.state::<Arc<dyn Storage>>().unwrap().clone();
+                let purger = Purger::new(
+                    purge_db,
+                    storage,
+                    app_config.trash_retention,
+                    app_config.metrics_retention,
+                );
+
+                tokio::spawn(purger.run());
+            })
+        }))
+        .manage(channel::<ProjectEvent>(1024).0)
         .mount("/", routes![health::health])
         .mount(
             "/projects",
             routes![
                 projects::get_projects,
                 projects::get_project,
+                projects::search_projects,
+                projects::get_project_by_slug,
                 projects::get_project_by_name,
+                projects::get_project_uptime,
                 projects::create_project,
                 projects::update_project,
+                projects::reorder_projects,
                 projects::delete_project,
+                projects::restore_project,
+                project_links::get_project_links,
+                project_links::create_project_link,
+                project_links::update_project_link,
+                project_links::delete_project_link,
+                project_media::get_project_media,
+                project_media::upload_project_media,
+                project_media::delete_project_media,
             ],
         )
         .mount(
@@ -67,10 +187,75 @@ pub fn rocket() -> _ {
             routes![
                 project_statuses::get_project_statuses,
                 project_statuses::get_project_status,
+                project_statuses::get_project_status_history,
+                project_statuses::get_project_status_uptime,
+                project_statuses::get_project_status_metrics,
                 project_statuses::get_project_statuses_by_project,
                 project_statuses::create_project_status,
                 project_statuses::update_project_status,
                 project_statuses::delete_project_status,
+                project_statuses::restore_project_status,
+            ],
+        )
+        .mount(
+            "/project_recipients",
+            routes![
+                project_recipients::get_project_recipients_by_project,
+                project_recipients::create_project_recipient,
+                project_recipients::delete_project_recipient,
+            ],
+        )
+        .mount(
+            "/incidents",
+            routes![
+                incidents::get_incidents,
+                incidents::get_incident,
+                incidents::get_incidents_by_project,
+                incidents::create_incident,
+                incidents::update_incident,
+                incidents::delete_incident,
+            ],
+        )
+        .mount(
+            "/maintenance_windows",
+            routes![
+                maintenance_windows::get_maintenance_window,
+                maintenance_windows::get_maintenance_windows_by_project,
+                maintenance_windows::get_maintenance_calendar,
+                maintenance_windows::create_maintenance_window,
+                maintenance_windows::update_maintenance_window,
+                maintenance_windows::delete_maintenance_window,
+            ],
+        )
+        .mount(
+            "/badges",
+            routes![badges::get_status_badge, badges::get_uptime_badge],
+        )
+        .mount(
+            "/tags",
+            routes![
+                tags::get_tags,
+                tags::get_tag,
+                tags::create_tag,
+                tags::update_tag,
+                tags::delete_tag
+            ],
+        )
+        .mount("/trash", routes![trash::get_trash])
+        .mount(
+            "/feeds",
+            routes![
+                feeds::get_atom_feed,
+                feeds::get_rss_feed,
+                feeds::get_project_atom_feed,
+                feeds::get_project_rss_feed
+            ],
+        )
+        .mount(
+            "/status",
+            routes![
+                status_page::get_status_page,
+                status_page::get_project_status_page,
             ],
         )
         .mount(
@@ -0,0 +1,84 @@
+use chrono::Utc;
+use std::time::Duration;
+use tokio::time::interval;
+
+use crate::{
+    db::{maintenance_windows, Db},
+    errors::DbError,
+    models::{
+        health_state::HealthState, project_status::ProjectStatus,
+        project_status_history::ReportSource,
+    },
+    reports::{Measurement, Report, Reporter, Reports},
+};
+
+const TICK: Duration = Duration::from_secs(30);
+
+/// Moves statuses into the maintenance state when one of their windows starts and
+/// into the unknown state once it ends, leaving their next report to tell how they
+/// came out of it rather than assuming the state they had going in.
+pub struct MaintenanceScheduler {
+    db: Db,
+    reports: Reports,
+}
+
+impl MaintenanceScheduler {
+    pub fn new(db: Db, reports: Reports) -> Self {
+        Self { db, reports }
+    }
+
+    pub async fn run(self) {
+        let mut ticker = interval(TICK);
+        loop {
+            ticker.tick().await;
+            self.apply_windows().await;
+        }
+    }
+
+    async fn apply_windows(&self) {
+        let now = Utc::now();
+        let due = self
+            .db
+            .run(move |conn| {
+                let starting = maintenance_windows::find_starting(conn, now)?;
+                let ending = maintenance_windows::find_ending(conn, now)?;
+                Ok::<_, DbError<'static>>((starting, ending))
+            })
+            .await;
+        let (starting, ending) = match due {
+            Ok(due) => due,
+            Err(e) => {
+                error!("Unable to load maintenance windows: {}", e);
+                return;
+            }
+        };
+
+        for project_status in starting {
+            self.apply(project_status, HealthState::Maintenance).await;
+        }
+        for project_status in ending {
+            self.apply(project_status, HealthState::Unknown).await;
+        }
+    }
+
+    async fn apply(&self, project_status: ProjectStatus, health_state: HealthState) {
+        let reporter = Reporter::new(ReportSource::Maintenance, None);
+        if let Err(e) = self
+            .reports
+            .save(
+                &self.db,
+                &project_status.project_status_id,
+                None,
+                Some(Report::Override(health_state)),
+                reporter,
+                Measurement::default(),
+            )
+            .await
+        {
+            error!(
+                "Applying maintenance to {} failed: {}",
+                project_status.project_status_id, e
+            );
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use pulldown_cmark::{html, Options, Parser};
+
+/// Renders CommonMark, with tables, strikethrough and task lists, to HTML stripped of
+/// scripts, styles, event handlers and anything else a browser could run.
+pub fn render(markdown: &str) -> String {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, Parser::new_ext(markdown, options));
+    ammonia::clean(&unsafe_html)
+}
@@ -0,0 +1,120 @@
+use image::{ImageFormat, ImageOutputFormat};
+use rocket::tokio::fs;
+use std::{
+    io::{self, Cursor, ErrorKind},
+    path::PathBuf,
+};
+
+use crate::models::project_media::ProjectMedia;
+
+/// Largest side of a thumbnail, in pixels.
+pub const THUMBNAIL_SIZE: u32 = 320;
+
+/// Room left in the `data-form` limit for the fields and boundaries around the file.
+pub const FORM_OVERHEAD_BYTES: u64 = 64 * 1024;
+
+pub struct MediaSettings {
+    /// Directory of the local storage backend, served under `/media`.
+    pub dir: PathBuf,
+    /// Largest upload accepted, in bytes. Rocket's `file` and `data-form` limits are
+    /// raised to match when it is built.
+    pub max_bytes: u64,
+}
+
+/// Where uploaded media is kept. Keys are relative paths such as
+/// `projects/<project_id>/<file>`.
+#[rocket::async_trait]
+pub trait Storage: Send + Sync {
+    /// Stores `bytes` under `key` and returns their public URL.
+    async fn put(&self, key: &str, content_type: &str, bytes: Vec<u8>) -> io::Result<String>;
+
+    /// Removes what is stored under `key`, if anything.
+    async fn delete(&self, key: &str) -> io::Result<()>;
+}
+
+/// Keeps media in a local directory served by Rocket's `FileServer`.
+pub struct LocalStorage {
+    root: PathBuf,
+    base_url: String,
+}
+
+impl LocalStorage {
+    pub fn new(root: PathBuf, base_url: String) -> Self {
+        Self { root, base_url }
+    }
+}
+
+#[rocket::async_trait]
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, _content_type: &str, bytes: Vec<u8>) -> io::Result<String> {
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(&path, bytes).await?;
+        Ok(format!("{}/{}", self.base_url, key))
+    }
+
+    async fn delete(&self, key: &str) -> io::Result<()> {
+        match fs::remove_file(self.root.join(key)).await {
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+}
+
+/// Deletes the files of media whose record is gone. Failures only leave orphaned
+/// files behind, so they are logged rather than returned.
+pub async fn remove(storage: &dyn Storage, media: &ProjectMedia) {
+    for key in [&media.storage_key, &media.thumbnail_key] {
+        if let Err(e) = storage.delete(key).await {
+            error!("Failed to delete media '{}': {}", key, e);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ImageError {
+    /// Not a PNG, JPEG, WebP or GIF image.
+    Unsupported,
+    /// Claims to be a supported image but can't be decoded.
+    Invalid,
+}
+
+/// A validated upload with its PNG thumbnail.
+pub struct Image {
+    pub content_type: &'static str,
+    pub extension: &'static str,
+    pub width: u32,
+    pub height: u32,
+    pub thumbnail: Vec<u8>,
+}
+
+/// Checks that `bytes` hold a supported image, going by its content rather than by
+/// what the client claims, and renders its thumbnail. Decoding is CPU-bound, so this
+/// is meant to run on a blocking thread.
+pub fn process(bytes: &[u8]) -> Result<Image, ImageError> {
+    let format = image::guess_format(bytes).map_err(|_| ImageError::Unsupported)?;
+    let (content_type, extension) = match format {
+        ImageFormat::Png => ("image/png", "png"),
+        ImageFormat::Jpeg => ("image/jpeg", "jpg"),
+        ImageFormat::WebP => ("image/webp", "webp"),
+        ImageFormat::Gif => ("image/gif", "gif"),
+        _ => return Err(ImageError::Unsupported),
+    };
+    let image =
+        image::load_from_memory_with_format(bytes, format).map_err(|_| ImageError::Invalid)?;
+
+    let mut thumbnail = Cursor::new(Vec::new());
+    image
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .write_to(&mut thumbnail, ImageOutputFormat::Png)
+        .map_err(|_| ImageError::Invalid)?;
+    Ok(Image {
+        content_type,
+        extension,
+        width: image.width(),
+        height: image.height(),
+        thumbnail: thumbnail.into_inner(),
+    })
+}
@@ -0,0 +1,12 @@
+use chrono::{DateTime, Utc};
+
+/// An entry of the Atom and RSS feeds: a status transition or an incident update.
+#[derive(Debug)]
+pub struct FeedEntry {
+    /// Id of the underlying history or timeline row, so readers see each entry once.
+    pub id: String,
+    pub project_name: String,
+    pub title: String,
+    pub summary: String,
+    pub published: DateTime<Utc>,
+}
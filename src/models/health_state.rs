@@ -0,0 +1,98 @@
+use diesel::{
+    deserialize::{self, FromSql, FromSqlRow},
+    expression::AsExpression,
+    pg::{Pg, PgValue},
+    serialize::{self, IsNull, Output, ToSql},
+};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+use crate::schema::sql_types;
+
+#[derive(
+    Serialize,
+    Deserialize,
+    FromFormField,
+    AsExpression,
+    FromSqlRow,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+)]
+#[diesel(sql_type = sql_types::HealthState)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthState {
+    Operational,
+    Degraded,
+    #[field(value = "partial_outage")]
+    PartialOutage,
+    #[field(value = "major_outage")]
+    MajorOutage,
+    Maintenance,
+    #[default]
+    Unknown,
+}
+
+impl HealthState {
+    /// The legacy `is_healthy` flag: a degraded service is still up.
+    pub fn is_healthy(self) -> bool {
+        matches!(self, HealthState::Operational | HealthState::Degraded)
+    }
+
+    /// A partial or major outage, as opposed to planned or unknown downtime.
+    pub fn is_outage(self) -> bool {
+        matches!(self, HealthState::PartialOutage | HealthState::MajorOutage)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HealthState::Operational => "Operational",
+            HealthState::Degraded => "Degraded performance",
+            HealthState::PartialOutage => "Partial outage",
+            HealthState::MajorOutage => "Major outage",
+            HealthState::Maintenance => "Under maintenance",
+            HealthState::Unknown => "Unknown",
+        }
+    }
+}
+
+impl From<bool> for HealthState {
+    fn from(is_healthy: bool) -> Self {
+        if is_healthy {
+            HealthState::Operational
+        } else {
+            HealthState::MajorOutage
+        }
+    }
+}
+
+impl ToSql<sql_types::HealthState, Pg> for HealthState {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            HealthState::Operational => out.write_all(b"operational")?,
+            HealthState::Degraded => out.write_all(b"degraded")?,
+            HealthState::PartialOutage => out.write_all(b"partial_outage")?,
+            HealthState::MajorOutage => out.write_all(b"major_outage")?,
+            HealthState::Maintenance => out.write_all(b"maintenance")?,
+            HealthState::Unknown => out.write_all(b"unknown")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::HealthState, Pg> for HealthState {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"operational" => Ok(HealthState::Operational),
+            b"degraded" => Ok(HealthState::Degraded),
+            b"partial_outage" => Ok(HealthState::PartialOutage),
+            b"major_outage" => Ok(HealthState::MajorOutage),
+            b"maintenance" => Ok(HealthState::Maintenance),
+            b"unknown" => Ok(HealthState::Unknown),
+            _ => Err("Unrecognized health state".into()),
+        }
+    }
+}
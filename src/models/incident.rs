@@ -0,0 +1,246 @@
+use chrono::{DateTime, Utc};
+use diesel::{
+    deserialize::{self, FromSql, FromSqlRow},
+    expression::AsExpression,
+    pg::{Pg, PgValue},
+    serialize::{self, IsNull, Output, ToSql},
+    AsChangeset, Associations, Identifiable, Insertable, Queryable,
+};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use uuid::Uuid;
+use validator_derive::Validate;
+
+use super::{health_state::HealthState, project::Project, project_status::ProjectStatus};
+use crate::schema::{incident_project_statuses, incident_timeline, incidents, sql_types};
+
+#[derive(Serialize, Deserialize, AsExpression, FromSqlRow, Clone, Copy, Debug, PartialEq, Eq)]
+#[diesel(sql_type = sql_types::IncidentSeverity)]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentSeverity {
+    Minor,
+    Major,
+    Critical,
+}
+
+impl ToSql<sql_types::IncidentSeverity, Pg> for IncidentSeverity {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            IncidentSeverity::Minor => out.write_all(b"minor")?,
+            IncidentSeverity::Major => out.write_all(b"major")?,
+            IncidentSeverity::Critical => out.write_all(b"critical")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::IncidentSeverity, Pg> for IncidentSeverity {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"minor" => Ok(IncidentSeverity::Minor),
+            b"major" => Ok(IncidentSeverity::Major),
+            b"critical" => Ok(IncidentSeverity::Critical),
+            _ => Err("Unrecognized incident severity".into()),
+        }
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, AsExpression, FromSqlRow, Clone, Copy, Debug, Default, PartialEq, Eq,
+)]
+#[diesel(sql_type = sql_types::IncidentState)]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentState {
+    #[default]
+    Investigating,
+    Identified,
+    Monitoring,
+    Resolved,
+}
+
+impl IncidentState {
+    pub fn label(self) -> &'static str {
+        match self {
+            IncidentState::Investigating => "investigating",
+            IncidentState::Identified => "identified",
+            IncidentState::Monitoring => "monitoring",
+            IncidentState::Resolved => "resolved",
+        }
+    }
+}
+
+impl ToSql<sql_types::IncidentState, Pg> for IncidentState {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            IncidentState::Investigating => out.write_all(b"investigating")?,
+            IncidentState::Identified => out.write_all(b"identified")?,
+            IncidentState::Monitoring => out.write_all(b"monitoring")?,
+            IncidentState::Resolved => out.write_all(b"resolved")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::IncidentState, Pg> for IncidentState {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"investigating" => Ok(IncidentState::Investigating),
+            b"identified" => Ok(IncidentState::Identified),
+            b"monitoring" => Ok(IncidentState::Monitoring),
+            b"resolved" => Ok(IncidentState::Resolved),
+            _ => Err("Unrecognized incident state".into()),
+        }
+    }
+}
+
+#[derive(
+    Serialize,
+    Deserialize,
+    Queryable,
+    Insertable,
+    AsChangeset,
+    Identifiable,
+    Associations,
+    Clone,
+    Debug,
+)]
+#[diesel(belongs_to(Project))]
+#[diesel(table_name = incidents)]
+#[diesel(primary_key(incident_id))]
+#[diesel(treat_none_as_null = true)]
+pub struct Incident {
+    pub incident_id: String,
+    pub project_id: String,
+    pub title: String,
+    pub severity: IncidentSeverity,
+    pub state: IncidentState,
+    /// Opened by a status going down rather than by hand; only these are resolved
+    /// automatically on recovery.
+    pub automatic: bool,
+    pub created_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+impl Incident {
+    /// The incident opened when `project_status` reports an outage.
+    pub fn automatic(project_status: &ProjectStatus, now: DateTime<Utc>) -> Self {
+        let (severity, title) = match project_status.health_state {
+            HealthState::MajorOutage => (
+                IncidentSeverity::Major,
+                format!("{} is down", project_status.name),
+            ),
+            _ => (
+                IncidentSeverity::Minor,
+                format!("{} is partially down", project_status.name),
+            ),
+        };
+        Self {
+            incident_id: Uuid::new_v4().to_string(),
+            project_id: project_status.project_id.clone(),
+            title,
+            severity,
+            state: IncidentState::Investigating,
+            automatic: true,
+            created_at: now,
+            resolved_at: None,
+        }
+    }
+
+    /// Moves the incident to `state`, stamping or clearing `resolved_at`.
+    pub fn set_state(&mut self, state: IncidentState, now: DateTime<Utc>) {
+        if state == IncidentState::Resolved && self.state != IncidentState::Resolved {
+            self.resolved_at = Some(now);
+        } else if state != IncidentState::Resolved {
+            self.resolved_at = None;
+        }
+        self.state = state;
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, Queryable, Insertable, Identifiable, Associations, Clone, Debug,
+)]
+#[diesel(belongs_to(Incident))]
+#[diesel(table_name = incident_timeline)]
+#[diesel(primary_key(incident_timeline_id))]
+pub struct IncidentTimelineEntry {
+    pub incident_timeline_id: String,
+    pub incident_id: String,
+    pub state: IncidentState,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl IncidentTimelineEntry {
+    pub fn new(incident: &Incident, message: String, now: DateTime<Utc>) -> Self {
+        Self {
+            incident_timeline_id: Uuid::new_v4().to_string(),
+            incident_id: incident.incident_id.clone(),
+            state: incident.state,
+            message,
+            created_at: now,
+        }
+    }
+}
+
+#[derive(Queryable, Insertable, Identifiable, Associations, Clone, Debug)]
+#[diesel(belongs_to(Incident))]
+#[diesel(belongs_to(ProjectStatus))]
+#[diesel(table_name = incident_project_statuses)]
+#[diesel(primary_key(incident_id, project_status_id))]
+pub struct IncidentProjectStatus {
+    pub incident_id: String,
+    pub project_status_id: String,
+}
+
+/// An incident with its timeline, oldest first, and the statuses it affects.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IncidentDetail {
+    #[serde(flatten)]
+    pub incident: Incident,
+    pub timeline: Vec<IncidentTimelineEntry>,
+    pub project_status_ids: Vec<String>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct IncidentCreate<'a> {
+    pub project_id: &'a str,
+    #[validate(length(
+        min = 1,
+        max = 256,
+        message = "Title must be between 1 and 256 characters"
+    ))]
+    pub title: &'a str,
+    pub severity: IncidentSeverity,
+    pub state: Option<IncidentState>,
+    #[validate(length(
+        min = 1,
+        max = 4096,
+        message = "Message must be between 1 and 4096 characters"
+    ))]
+    pub message: &'a str,
+    #[serde(default)]
+    pub project_status_ids: Vec<String>,
+}
+
+/// A `message` or `state` adds an entry to the incident's timeline.
+#[derive(Deserialize, Validate, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct IncidentUpdate<'a> {
+    #[validate(length(
+        min = 1,
+        max = 256,
+        message = "Title must be between 1 and 256 characters"
+    ))]
+    pub title: Option<&'a str>,
+    pub severity: Option<IncidentSeverity>,
+    pub state: Option<IncidentState>,
+    #[validate(length(
+        min = 1,
+        max = 4096,
+        message = "Message must be between 1 and 4096 characters"
+    ))]
+    pub message: Option<&'a str>,
+    pub project_status_ids: Option<Vec<String>>,
+}
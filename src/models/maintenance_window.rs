@@ -0,0 +1,98 @@
+use chrono::{DateTime, Utc};
+use diesel::{AsChangeset, Associations, Identifiable, Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use validator::{ValidationError, ValidationErrors};
+use validator_derive::Validate;
+
+use super::{project::Project, project_status::ProjectStatus};
+use crate::schema::{maintenance_window_project_statuses, maintenance_windows};
+
+/// Planned downtime: while a window is active its statuses are held in the
+/// maintenance state and no alerts are sent for them.
+#[derive(
+    Serialize,
+    Deserialize,
+    Queryable,
+    Insertable,
+    AsChangeset,
+    Identifiable,
+    Associations,
+    Clone,
+    Debug,
+)]
+#[diesel(belongs_to(Project))]
+#[diesel(table_name = maintenance_windows)]
+#[diesel(primary_key(maintenance_window_id))]
+pub struct MaintenanceWindow {
+    pub maintenance_window_id: String,
+    pub project_id: String,
+    pub message: String,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl MaintenanceWindow {
+    pub fn validate_period(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if self.ends_at <= self.starts_at {
+            let mut error = ValidationError::new("period");
+            error.message = Some(Cow::from("End must be after start"));
+            errors.add("ends_at", error);
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Queryable, Insertable, Identifiable, Associations, Clone, Debug)]
+#[diesel(belongs_to(MaintenanceWindow))]
+#[diesel(belongs_to(ProjectStatus))]
+#[diesel(table_name = maintenance_window_project_statuses)]
+#[diesel(primary_key(maintenance_window_id, project_status_id))]
+pub struct MaintenanceWindowProjectStatus {
+    pub maintenance_window_id: String,
+    pub project_status_id: String,
+}
+
+/// A maintenance window with the statuses it affects.
+#[derive(Serialize, Debug)]
+pub struct MaintenanceWindowDetail {
+    #[serde(flatten)]
+    pub maintenance_window: MaintenanceWindow,
+    pub project_status_ids: Vec<String>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct MaintenanceWindowCreate<'a> {
+    pub project_id: &'a str,
+    #[validate(length(
+        min = 1,
+        max = 4096,
+        message = "Message must be between 1 and 4096 characters"
+    ))]
+    pub message: &'a str,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    #[serde(default)]
+    pub project_status_ids: Vec<String>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct MaintenanceWindowUpdate<'a> {
+    #[validate(length(
+        min = 1,
+        max = 4096,
+        message = "Message must be between 1 and 4096 characters"
+    ))]
+    pub message: Option<&'a str>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub project_status_ids: Option<Vec<String>>,
+}
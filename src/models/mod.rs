@@ -1,2 +1,19 @@
+pub mod feed;
+pub mod health_state;
+pub mod incident;
+pub mod maintenance_window;
 pub mod project;
+pub mod project_event;
+pub mod project_link;
+pub mod project_media;
+pub mod project_recipient;
 pub mod project_status;
+pub mod project_status_history;
+pub mod project_status_metric;
+pub mod rollup;
+pub mod sort;
+pub mod status_page;
+pub mod tag;
+pub mod trash;
+pub mod uptime;
+pub mod visibility;
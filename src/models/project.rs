@@ -1,10 +1,25 @@
-use diesel::{AsChangeset, Identifiable, Insertable, Queryable};
+use chrono::{DateTime, Utc};
+use diesel::{
+    sql_types::{Float4, Text},
+    AsChangeset, Associations, Identifiable, Insertable, Queryable, QueryableByName,
+};
 use serde::{Deserialize, Serialize};
 use validator_derive::Validate;
 
-use crate::schema::projects;
+use super::{
+    project_link::ProjectLink,
+    project_media::ProjectMedia,
+    project_status::ProjectStatus,
+    rollup::{ProjectHealth, RollupPolicy},
+    tag::{self, Tag},
+    visibility::Visibility,
+};
+use crate::{
+    escape,
+    schema::{project_slug_redirects, projects},
+};
 
-#[derive(Serialize, Queryable, Insertable, AsChangeset, Identifiable, Debug)]
+#[derive(Serialize, Queryable, QueryableByName, Insertable, AsChangeset, Identifiable, Debug)]
 #[diesel(table_name = projects)]
 #[diesel(primary_key(project_id))]
 pub struct Project {
@@ -13,6 +28,178 @@ pub struct Project {
     pub description: Option<String>,
     pub url: String,
     pub github_repository: String,
+    pub rollup_policy: RollupPolicy,
+    pub created_at: DateTime<Utc>,
+    /// Kept current by the `set_updated_at` trigger.
+    pub updated_at: DateTime<Utc>,
+    /// Unique URL-safe identifier derived from the name. Previous slugs keep
+    /// resolving through `ProjectSlugRedirect`.
+    pub slug: String,
+    /// `description` rendered from Markdown and sanitized, set whenever it changes.
+    pub description_html: Option<String>,
+    /// Highlighted by the frontend; doesn't affect the order.
+    pub featured: bool,
+    /// Place in the default project order, lowest first. Rewritten by reordering.
+    pub position: i32,
+    pub visibility: Visibility,
+    /// Set while the project is in the trash.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl Project {
+    /// The URL-safe form of `name`: lowercase ASCII letters and digits separated by
+    /// single dashes.
+    pub fn slugify(name: &str) -> String {
+        let slug = name
+            .to_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<&str>>()
+            .join("-");
+        if slug.is_empty() {
+            String::from("project")
+        } else {
+            slug
+        }
+    }
+}
+
+/// The columns a project update writes. `position` is left out since only reordering
+/// rewrites it, and `updated_at` is left to the trigger.
+#[derive(AsChangeset)]
+#[diesel(table_name = projects)]
+pub struct ProjectChangeset<'a> {
+    pub name: &'a str,
+    pub description: Option<&'a str>,
+    pub url: &'a str,
+    pub github_repository: &'a str,
+    pub rollup_policy: RollupPolicy,
+    pub slug: &'a str,
+    pub description_html: Option<&'a str>,
+    pub featured: bool,
+    pub visibility: Visibility,
+}
+
+impl<'a> From<&'a Project> for ProjectChangeset<'a> {
+    fn from(project: &'a Project) -> Self {
+        Self {
+            name: &project.name,
+            description: project.description.as_deref(),
+            url: &project.url,
+            github_repository: &project.github_repository,
+            rollup_policy: project.rollup_policy,
+            slug: &project.slug,
+            description_html: project.description_html.as_deref(),
+            featured: project.featured,
+            visibility: project.visibility,
+        }
+    }
+}
+
+/// A former slug of a project, kept so that old links still resolve after a rename.
+#[derive(Queryable, Insertable, Identifiable, Associations, Clone, Debug)]
+#[diesel(belongs_to(Project))]
+#[diesel(table_name = project_slug_redirects)]
+#[diesel(primary_key(slug))]
+pub struct ProjectSlugRedirect {
+    pub slug: String,
+    pub project_id: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A project with its tag names, its links, its media and, when asked for, its rolled
+/// up health and its statuses.
+#[derive(Serialize, Debug)]
+pub struct ProjectDetail {
+    #[serde(flatten)]
+    pub project: Project,
+    pub tags: Vec<String>,
+    pub links: Vec<ProjectLink>,
+    pub media: Vec<ProjectMedia>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health: Option<ProjectHealth>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_statuses: Option<Vec<ProjectStatus>>,
+}
+
+impl ProjectDetail {
+    pub fn new(
+        project: Project,
+        tags: Vec<Tag>,
+        links: Vec<ProjectLink>,
+        media: Vec<ProjectMedia>,
+        project_statuses: Vec<ProjectStatus>,
+        with_health: bool,
+        with_statuses: bool,
+    ) -> Self {
+        let health = with_health.then(|| project.rollup_policy.apply(&project_statuses));
+        Self {
+            project,
+            tags: tags.into_iter().map(|tag| tag.name).collect(),
+            links,
+            media,
+            health,
+            project_statuses: with_statuses.then_some(project_statuses),
+        }
+    }
+}
+
+/// A project matching a search, with the matches in its name and description
+/// wrapped in `<mark>` tags.
+#[derive(Serialize, QueryableByName, Debug)]
+pub struct ProjectSearchResult {
+    #[serde(flatten)]
+    #[diesel(embed)]
+    pub project: Project,
+    #[diesel(sql_type = Float4)]
+    pub rank: f32,
+    /// HTML-safe: escaped apart from the `<mark>` tags.
+    #[diesel(sql_type = Text)]
+    pub name_highlight: String,
+    /// HTML-safe: escaped apart from the `<mark>` tags.
+    #[diesel(sql_type = Text)]
+    pub description_highlight: String,
+}
+
+impl ProjectSearchResult {
+    /// Private use characters `ts_headline` puts around matches in place of the
+    /// `<mark>` tags, so the text can be escaped before the tags go in.
+    pub const START_SEL: char = '\u{E000}';
+    pub const STOP_SEL: char = '\u{E001}';
+
+    /// Escapes the highlights as loaded and turns their selection markers into
+    /// `<mark>` tags.
+    pub fn escape_highlights(mut self) -> Self {
+        self.name_highlight = Self::mark(&self.name_highlight);
+        self.description_highlight = Self::mark(&self.description_highlight);
+        self
+    }
+
+    fn mark(highlight: &str) -> String {
+        escape::markup(highlight)
+            .replace(Self::START_SEL, "<mark>")
+            .replace(Self::STOP_SEL, "</mark>")
+    }
+}
+
+/// A search result with the tags, links and media of its project.
+#[derive(Debug)]
+pub struct ProjectSearchDetail {
+    pub detail: ProjectDetail,
+    pub rank: f32,
+    pub name_highlight: String,
+    pub description_highlight: String,
+}
+
+/// Field filters of the project list, matched exactly.
+#[derive(FromForm, Default, Debug)]
+pub struct ProjectFilter {
+    pub name: Option<String>,
+    pub tag: Option<String>,
+    pub featured: Option<bool>,
+    /// Forced to `published` for unauthenticated requests.
+    pub visibility: Option<Visibility>,
 }
 
 #[derive(Deserialize, Validate, Debug)]
@@ -24,11 +211,20 @@ pub struct ProjectCreate<'a> {
         message = "Name must be between 5 and 16 characters"
     ))]
     pub name: &'a str,
+    /// Markdown, rendered to `description_html`.
+    #[validate(length(max = 8192, message = "Description must be at most 8192 characters"))]
     pub description: Option<&'a str>,
     #[validate(url(message = "Value is not a valid URL"))]
     pub url: &'a str,
     #[validate(url(message = "Value is not a valid URL"))]
     pub github_repository: &'a str,
+    pub rollup_policy: Option<RollupPolicy>,
+    pub featured: Option<bool>,
+    /// `published` when not given.
+    pub visibility: Option<Visibility>,
+    /// Names of the project's tags, created if they don't exist yet.
+    #[validate(custom = "tag::validate_names")]
+    pub tags: Option<Vec<String>>,
 }
 
 #[derive(Deserialize, Validate, Debug)]
@@ -40,9 +236,47 @@ pub struct ProjectUpdate<'a> {
         message = "Name must be between 5 and 16 characters"
     ))]
     pub name: Option<&'a str>,
+    /// Markdown, rendered to `description_html`.
+    #[validate(length(max = 8192, message = "Description must be at most 8192 characters"))]
     pub description: Option<&'a str>,
     #[validate(url(message = "Value is not a valid URL"))]
     pub url: Option<&'a str>,
     #[validate(url(message = "Value is not a valid URL"))]
     pub github_repository: Option<&'a str>,
+    pub rollup_policy: Option<RollupPolicy>,
+    pub featured: Option<bool>,
+    pub visibility: Option<Visibility>,
+    /// Replaces the project's tags when given.
+    #[validate(custom = "tag::validate_names")]
+    pub tags: Option<Vec<String>>,
+}
+
+/// The new order of the projects: the listed ones come first, in the given order.
+#[derive(Deserialize, Validate, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct ProjectReorder {
+    #[validate(length(min = 1, message = "At least one project must be given"))]
+    pub project_ids: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugify_joins_lowercase_words_with_dashes() {
+        assert_eq!(Project::slugify("Hello, World!"), "hello-world");
+        assert_eq!(Project::slugify("  my_project  2 "), "my-project-2");
+    }
+
+    #[test]
+    fn slugify_drops_non_ascii_characters() {
+        assert_eq!(Project::slugify("Crème brûlée"), "cr-me-br-l-e");
+    }
+
+    #[test]
+    fn slugify_falls_back_when_nothing_is_left() {
+        assert_eq!(Project::slugify("!!!"), "project");
+        assert_eq!(Project::slugify(""), "project");
+    }
 }
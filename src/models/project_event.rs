@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+use super::{incident::IncidentDetail, project_status::ProjectStatus};
+
+/// Payload of the `project_events` stream: the status as it is after a report, plus
+/// the measurements that came with the report.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProjectStatusEvent {
+    #[serde(flatten)]
+    pub project_status: ProjectStatus,
+    pub latency_ms: Option<i32>,
+    pub detail: Option<String>,
+}
+
+/// Everything published on the `project_events` stream. Status events keep the
+/// stream's original shape; incidents are sent as `incident` events.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum ProjectEvent {
+    ProjectStatus(ProjectStatusEvent),
+    Incident(IncidentDetail),
+}
+
+impl ProjectEvent {
+    /// The project the event is about.
+    pub fn project_id(&self) -> &str {
+        match self {
+            ProjectEvent::ProjectStatus(event) => &event.project_status.project_id,
+            ProjectEvent::Incident(incident) => &incident.incident.project_id,
+        }
+    }
+
+    /// Name of the server-sent event, `None` for the default `message`.
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            ProjectEvent::ProjectStatus(_) => None,
+            ProjectEvent::Incident(_) => Some("incident"),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Utc};
+use diesel::{
+    deserialize::{self, FromSql, FromSqlRow},
+    expression::AsExpression,
+    pg::{Pg, PgValue},
+    serialize::{self, IsNull, Output, ToSql},
+    AsChangeset, Associations, Identifiable, Insertable, Queryable,
+};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use validator_derive::Validate;
+
+use super::project::Project;
+use crate::schema::{project_links, sql_types};
+
+#[derive(
+    Serialize, Deserialize, AsExpression, FromSqlRow, Clone, Copy, Debug, Default, PartialEq, Eq,
+)]
+#[diesel(sql_type = sql_types::LinkKind)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkKind {
+    Homepage,
+    Repository,
+    Docs,
+    Demo,
+    CratesIo,
+    Npm,
+    Pypi,
+    #[default]
+    Other,
+}
+
+impl ToSql<sql_types::LinkKind, Pg> for LinkKind {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            LinkKind::Homepage => out.write_all(b"homepage")?,
+            LinkKind::Repository => out.write_all(b"repository")?,
+            LinkKind::Docs => out.write_all(b"docs")?,
+            LinkKind::Demo => out.write_all(b"demo")?,
+            LinkKind::CratesIo => out.write_all(b"crates_io")?,
+            LinkKind::Npm => out.write_all(b"npm")?,
+            LinkKind::Pypi => out.write_all(b"pypi")?,
+            LinkKind::Other => out.write_all(b"other")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::LinkKind, Pg> for LinkKind {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"homepage" => Ok(LinkKind::Homepage),
+            b"repository" => Ok(LinkKind::Repository),
+            b"docs" => Ok(LinkKind::Docs),
+            b"demo" => Ok(LinkKind::Demo),
+            b"crates_io" => Ok(LinkKind::CratesIo),
+            b"npm" => Ok(LinkKind::Npm),
+            b"pypi" => Ok(LinkKind::Pypi),
+            b"other" => Ok(LinkKind::Other),
+            _ => Err("Unrecognized link kind".into()),
+        }
+    }
+}
+
+/// A link besides the project's primary `url` and `github_repository`.
+#[derive(
+    Serialize, Queryable, Insertable, AsChangeset, Identifiable, Associations, Clone, Debug,
+)]
+#[diesel(belongs_to(Project))]
+#[diesel(table_name = project_links)]
+#[diesel(primary_key(project_link_id))]
+pub struct ProjectLink {
+    pub project_link_id: String,
+    pub project_id: String,
+    pub kind: LinkKind,
+    pub url: String,
+    pub label: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct ProjectLinkCreate<'a> {
+    pub kind: Option<LinkKind>,
+    #[validate(url(message = "Value is not a valid URL"))]
+    pub url: &'a str,
+    #[validate(length(max = 64, message = "Label must be at most 64 characters"))]
+    pub label: Option<&'a str>,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct ProjectLinkUpdate<'a> {
+    pub kind: Option<LinkKind>,
+    #[validate(url(message = "Value is not a valid URL"))]
+    pub url: Option<&'a str>,
+    #[validate(length(max = 64, message = "Label must be at most 64 characters"))]
+    pub label: Option<&'a str>,
+}
@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use diesel::{
+    deserialize::{self, FromSql, FromSqlRow},
+    expression::AsExpression,
+    pg::{Pg, PgValue},
+    serialize::{self, IsNull, Output, ToSql},
+    Associations, Identifiable, Insertable, Queryable,
+};
+use rocket::{data::Capped, fs::TempFile};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use validator_derive::Validate;
+
+use super::project::Project;
+use crate::schema::{project_media, sql_types};
+
+#[derive(
+    Serialize,
+    Deserialize,
+    FromFormField,
+    AsExpression,
+    FromSqlRow,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+)]
+#[diesel(sql_type = sql_types::MediaKind)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaKind {
+    #[default]
+    Screenshot,
+    /// A project has at most one cover: uploading another one replaces it.
+    Cover,
+}
+
+impl ToSql<sql_types::MediaKind, Pg> for MediaKind {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            MediaKind::Screenshot => out.write_all(b"screenshot")?,
+            MediaKind::Cover => out.write_all(b"cover")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::MediaKind, Pg> for MediaKind {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"screenshot" => Ok(MediaKind::Screenshot),
+            b"cover" => Ok(MediaKind::Cover),
+            _ => Err("Unrecognized media kind".into()),
+        }
+    }
+}
+
+/// An uploaded image. `url` and `thumbnail_url` are the public URLs the storage
+/// backend gave when the files were stored under their keys.
+#[derive(Serialize, Queryable, Insertable, Identifiable, Associations, Clone, Debug)]
+#[diesel(belongs_to(Project))]
+#[diesel(table_name = project_media)]
+#[diesel(primary_key(project_media_id))]
+pub struct ProjectMedia {
+    pub project_media_id: String,
+    pub project_id: String,
+    pub kind: MediaKind,
+    pub content_type: String,
+    pub byte_size: i32,
+    pub width: i32,
+    pub height: i32,
+    pub caption: Option<String>,
+    pub url: String,
+    pub thumbnail_url: String,
+    #[serde(skip_serializing)]
+    pub storage_key: String,
+    #[serde(skip_serializing)]
+    pub thumbnail_key: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A `multipart/form-data` upload. The file is capped at the `file` limit rather than
+/// rejected by Rocket, so uploads over it get a clear error.
+#[derive(FromForm, Validate, Debug)]
+pub struct ProjectMediaUpload<'r> {
+    pub file: Capped<TempFile<'r>>,
+    pub kind: Option<MediaKind>,
+    #[validate(length(max = 256, message = "Caption must be at most 256 characters"))]
+    pub caption: Option<String>,
+}
@@ -0,0 +1,26 @@
+use diesel::{Associations, Identifiable, Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+use validator_derive::Validate;
+
+use super::project::Project;
+use crate::schema::project_recipients;
+
+#[derive(
+    Serialize, Deserialize, Queryable, Insertable, Identifiable, Associations, Clone, Debug,
+)]
+#[diesel(belongs_to(Project))]
+#[diesel(table_name = project_recipients)]
+#[diesel(primary_key(project_recipient_id))]
+pub struct ProjectRecipient {
+    pub project_recipient_id: String,
+    pub email: String,
+    pub project_id: String,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct ProjectRecipientCreate<'a> {
+    #[validate(email(message = "Value is not a valid email"))]
+    pub email: &'a str,
+    pub project_id: &'a str,
+}
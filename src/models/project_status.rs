@@ -1,9 +1,55 @@
-use diesel::{AsChangeset, Associations, Identifiable, Insertable, Queryable};
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, Utc};
+use diesel::{
+    deserialize::{self, FromSql, FromSqlRow},
+    expression::AsExpression,
+    pg::{Pg, PgValue},
+    serialize::{self, IsNull, Output, ToSql},
+    AsChangeset, Associations, Identifiable, Insertable, Queryable,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{borrow::Cow, io::Write};
+use validator::{validate_url, ValidationError, ValidationErrors};
 use validator_derive::Validate;
 
-use super::project::Project;
-use crate::schema::project_statuses;
+use super::{health_state::HealthState, project::Project};
+use crate::schema::{project_statuses, sql_types};
+
+#[derive(
+    Serialize, Deserialize, AsExpression, FromSqlRow, Clone, Copy, Debug, Default, PartialEq, Eq,
+)]
+#[diesel(sql_type = sql_types::CheckKind)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckKind {
+    #[default]
+    Http,
+    Tcp,
+    GrpcHealth,
+    Keyword,
+}
+
+impl ToSql<sql_types::CheckKind, Pg> for CheckKind {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            CheckKind::Http => out.write_all(b"http")?,
+            CheckKind::Tcp => out.write_all(b"tcp")?,
+            CheckKind::GrpcHealth => out.write_all(b"grpc_health")?,
+            CheckKind::Keyword => out.write_all(b"keyword")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::CheckKind, Pg> for CheckKind {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"http" => Ok(CheckKind::Http),
+            b"tcp" => Ok(CheckKind::Tcp),
+            b"grpc_health" => Ok(CheckKind::GrpcHealth),
+            b"keyword" => Ok(CheckKind::Keyword),
+            _ => Err("Unrecognized check kind".into()),
+        }
+    }
+}
 
 #[derive(
     Serialize,
@@ -15,6 +61,7 @@ use crate::schema::project_statuses;
     Associations,
     Clone,
     Debug,
+    PartialEq,
 )]
 #[diesel(belongs_to(Project))]
 #[diesel(table_name = project_statuses)]
@@ -24,6 +71,174 @@ pub struct ProjectStatus {
     pub name: String,
     pub is_healthy: bool,
     pub project_id: String,
+    #[serde(default)]
+    pub check_enabled: bool,
+    pub check_target: Option<String>,
+    pub check_expected_status: Option<i32>,
+    pub check_expected_body: Option<String>,
+    pub check_max_latency_ms: Option<i32>,
+    #[serde(default)]
+    pub check_kind: CheckKind,
+    pub check_interval_secs: Option<i32>,
+    pub check_timeout_ms: Option<i32>,
+    pub check_grpc_service: Option<String>,
+    #[serde(default = "default_threshold")]
+    pub failure_threshold: i32,
+    #[serde(default = "default_threshold")]
+    pub success_threshold: i32,
+    #[serde(default)]
+    pub pending_reports: i32,
+    #[serde(default)]
+    pub flapping: bool,
+    /// How often the reported health flipped since `flap_window_started_at`.
+    #[serde(default, skip_serializing)]
+    pub flap_transitions: i32,
+    #[serde(default, skip_serializing)]
+    pub flap_window_started_at: DateTime<Utc>,
+    #[serde(default)]
+    pub health_state: HealthState,
+    #[serde(default)]
+    pub created_at: DateTime<Utc>,
+    /// Kept current by the `set_updated_at` trigger.
+    #[serde(default)]
+    pub updated_at: DateTime<Utc>,
+    /// Set while the status is in the trash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+fn default_threshold() -> i32 {
+    1
+}
+
+/// How many minutes reported health flips are counted for before the count starts
+/// over.
+const FLAP_WINDOW_MINUTES: i64 = 10;
+/// How many flips within the flap window make a status flapping.
+const FLAP_TRANSITIONS: i32 = 4;
+
+impl ProjectStatus {
+    /// Applies a health report with hysteresis. Reports that would flip `is_healthy`
+    /// are counted and only applied once `failure_threshold` (or `success_threshold`)
+    /// consecutive ones have been received. The status is `flapping` while the
+    /// reported health has flipped at least `FLAP_TRANSITIONS` times within
+    /// `FLAP_WINDOW_MINUTES`, whether or not the flips got past the thresholds.
+    pub fn record_report(&mut self, health_state: HealthState, now: DateTime<Utc>) {
+        let flap_window = Duration::minutes(FLAP_WINDOW_MINUTES);
+        // Pending reports are the ones that disagreed with the applied state.
+        let last_reported_healthy = self.is_healthy != (self.pending_reports > 0);
+        if health_state.is_healthy() != last_reported_healthy {
+            if now - self.flap_window_started_at >= flap_window {
+                self.flap_window_started_at = now;
+                self.flap_transitions = 0;
+            }
+            self.flap_transitions += 1;
+        }
+        self.flapping = self.flap_transitions >= FLAP_TRANSITIONS
+            && now - self.flap_window_started_at < flap_window;
+
+        if health_state.is_healthy() == self.is_healthy {
+            self.apply_health_state(health_state);
+            return;
+        }
+
+        let threshold = if health_state.is_healthy() {
+            self.success_threshold
+        } else {
+            self.failure_threshold
+        };
+        self.pending_reports += 1;
+        if self.pending_reports >= threshold {
+            self.apply_health_state(health_state);
+        }
+    }
+
+    /// Sets the health state outright, bypassing the thresholds and starting flap
+    /// detection over.
+    pub fn set_health_state(&mut self, health_state: HealthState) {
+        self.apply_health_state(health_state);
+        self.flapping = false;
+        self.flap_transitions = 0;
+    }
+
+    /// Takes the health state and the hysteresis and flap counters from `other`,
+    /// leaving the rest of the status alone.
+    pub fn take_health_of(&mut self, other: &ProjectStatus) {
+        self.is_healthy = other.is_healthy;
+        self.health_state = other.health_state;
+        self.pending_reports = other.pending_reports;
+        self.flapping = other.flapping;
+        self.flap_transitions = other.flap_transitions;
+        self.flap_window_started_at = other.flap_window_started_at;
+    }
+
+    fn apply_health_state(&mut self, health_state: HealthState) {
+        self.health_state = health_state;
+        self.is_healthy = health_state.is_healthy();
+        self.pending_reports = 0;
+    }
+
+    /// Validates the check target and expectations against the check kind, since
+    /// which fields are required depends on the kind.
+    pub fn validate_check(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        let target = self.check_target.as_deref();
+        match self.check_kind {
+            CheckKind::Http | CheckKind::Keyword => {
+                if target.map_or(false, |t| !validate_url(t)) {
+                    errors.add("check_target", check_error("Value is not a valid URL"));
+                }
+            }
+            CheckKind::GrpcHealth => {
+                if !target.map_or(false, validate_url) {
+                    errors.add(
+                        "check_target",
+                        check_error("gRPC health checks require a valid URL target"),
+                    );
+                }
+            }
+            CheckKind::Tcp => {
+                let is_host_port = target
+                    .and_then(|t| t.rsplit_once(':'))
+                    .map_or(false, |(host, port)| {
+                        !host.is_empty() && port.parse::<u16>().is_ok()
+                    });
+                if !is_host_port {
+                    errors.add(
+                        "check_target",
+                        check_error("TCP checks require a 'host:port' target"),
+                    );
+                }
+            }
+        }
+        if self.check_kind == CheckKind::Keyword && self.check_expected_body.is_none() {
+            errors.add(
+                "check_expected_body",
+                check_error("Keyword checks require an expected body"),
+            );
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn check_error(message: &'static str) -> ValidationError {
+    let mut error = ValidationError::new("check");
+    error.message = Some(Cow::from(message));
+    error
+}
+
+/// Field filters of the project status list, matched exactly.
+#[derive(FromForm, Default, Debug)]
+pub struct ProjectStatusFilter {
+    pub project_id: Option<String>,
+    pub is_healthy: Option<bool>,
+    pub health_state: Option<HealthState>,
+    pub check_enabled: Option<bool>,
 }
 
 #[derive(Deserialize, Validate, Debug)]
@@ -36,6 +251,44 @@ pub struct ProjectStatusCreate<'a> {
     ))]
     pub name: &'a str,
     pub project_id: &'a str,
+    pub check_enabled: Option<bool>,
+    pub check_kind: Option<CheckKind>,
+    #[validate(length(min = 1, message = "Target must not be empty"))]
+    pub check_target: Option<&'a str>,
+    #[validate(range(
+        min = 100,
+        max = 599,
+        message = "Expected status must be between 100 and 599"
+    ))]
+    pub check_expected_status: Option<i32>,
+    pub check_expected_body: Option<&'a str>,
+    #[validate(range(min = 1, message = "Max latency must be at least 1 ms"))]
+    pub check_max_latency_ms: Option<i32>,
+    #[validate(range(
+        min = 5,
+        max = 86400,
+        message = "Interval must be between 5 and 86400 seconds"
+    ))]
+    pub check_interval_secs: Option<i32>,
+    #[validate(range(
+        min = 1,
+        max = 60000,
+        message = "Timeout must be between 1 and 60000 ms"
+    ))]
+    pub check_timeout_ms: Option<i32>,
+    pub check_grpc_service: Option<&'a str>,
+    #[validate(range(
+        min = 1,
+        max = 100,
+        message = "Failure threshold must be between 1 and 100"
+    ))]
+    pub failure_threshold: Option<i32>,
+    #[validate(range(
+        min = 1,
+        max = 100,
+        message = "Success threshold must be between 1 and 100"
+    ))]
+    pub success_threshold: Option<i32>,
 }
 
 #[derive(Deserialize, Validate, Debug)]
@@ -48,5 +301,205 @@ pub struct ProjectStatusUpdate<'a> {
     ))]
     pub name: Option<&'a str>,
     pub is_healthy: Option<bool>,
+    pub health_state: Option<HealthState>,
     pub project_id: Option<&'a str>,
+    pub check_enabled: Option<bool>,
+    pub check_kind: Option<CheckKind>,
+    #[validate(length(min = 1, message = "Target must not be empty"))]
+    #[serde(default, borrow, deserialize_with = "double_option")]
+    pub check_target: Option<Option<&'a str>>,
+    #[validate(range(
+        min = 100,
+        max = 599,
+        message = "Expected status must be between 100 and 599"
+    ))]
+    #[serde(default, deserialize_with = "double_option")]
+    pub check_expected_status: Option<Option<i32>>,
+    #[serde(default, borrow, deserialize_with = "double_option")]
+    pub check_expected_body: Option<Option<&'a str>>,
+    #[validate(range(min = 1, message = "Max latency must be at least 1 ms"))]
+    #[serde(default, deserialize_with = "double_option")]
+    pub check_max_latency_ms: Option<Option<i32>>,
+    #[validate(range(
+        min = 5,
+        max = 86400,
+        message = "Interval must be between 5 and 86400 seconds"
+    ))]
+    #[serde(default, deserialize_with = "double_option")]
+    pub check_interval_secs: Option<Option<i32>>,
+    #[validate(range(
+        min = 1,
+        max = 60000,
+        message = "Timeout must be between 1 and 60000 ms"
+    ))]
+    #[serde(default, deserialize_with = "double_option")]
+    pub check_timeout_ms: Option<Option<i32>>,
+    #[serde(default, borrow, deserialize_with = "double_option")]
+    pub check_grpc_service: Option<Option<&'a str>>,
+    #[validate(range(
+        min = 1,
+        max = 100,
+        message = "Failure threshold must be between 1 and 100"
+    ))]
+    pub failure_threshold: Option<i32>,
+    #[validate(range(
+        min = 1,
+        max = 100,
+        message = "Success threshold must be between 1 and 100"
+    ))]
+    pub success_threshold: Option<i32>,
+    #[validate(range(min = 0, message = "Latency must not be negative"))]
+    pub latency_ms: Option<i32>,
+    #[validate(length(max = 1024, message = "Detail must be at most 1024 characters"))]
+    pub detail: Option<&'a str>,
+}
+
+/// Tells a `null` field, which clears the value, apart from a missing one, which
+/// leaves it unchanged: the former is `Some(None)`, the latter `None`.
+fn double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use rocket::serde::json::{json, serde_json};
+
+    use super::*;
+
+    fn project_status(failure_threshold: i32, success_threshold: i32) -> ProjectStatus {
+        serde_json::from_value(json!({
+            "project_status_id": "status",
+            "name": "api",
+            "is_healthy": true,
+            "project_id": "project",
+            "health_state": "operational",
+            "failure_threshold": failure_threshold,
+            "success_threshold": success_threshold,
+        }))
+        .unwrap()
+    }
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2023, 5, 1, 12, 0, 0).unwrap() + Duration::minutes(minutes)
+    }
+
+    #[test]
+    fn reports_below_the_threshold_are_held_back() {
+        let mut status = project_status(3, 1);
+        status.record_report(HealthState::MajorOutage, at(0));
+        status.record_report(HealthState::MajorOutage, at(1));
+        assert!(status.is_healthy);
+        assert_eq!(status.health_state, HealthState::Operational);
+        assert_eq!(status.pending_reports, 2);
+
+        status.record_report(HealthState::MajorOutage, at(2));
+        assert!(!status.is_healthy);
+        assert_eq!(status.health_state, HealthState::MajorOutage);
+        assert_eq!(status.pending_reports, 0);
+    }
+
+    #[test]
+    fn agreeing_report_resets_the_pending_count() {
+        let mut status = project_status(2, 1);
+        status.record_report(HealthState::MajorOutage, at(0));
+        status.record_report(HealthState::Degraded, at(1));
+        assert!(status.is_healthy);
+        assert_eq!(status.health_state, HealthState::Degraded);
+        assert_eq!(status.pending_reports, 0);
+
+        status.record_report(HealthState::MajorOutage, at(2));
+        assert!(status.is_healthy);
+    }
+
+    #[test]
+    fn frequent_flips_are_flapping() {
+        let mut status = project_status(1, 1);
+        let reports = [
+            HealthState::MajorOutage,
+            HealthState::Operational,
+            HealthState::MajorOutage,
+        ];
+        for (minute, health_state) in reports.into_iter().enumerate() {
+            status.record_report(health_state, at(minute as i64));
+            assert!(!status.flapping);
+        }
+        status.record_report(HealthState::Operational, at(3));
+        assert!(status.flapping);
+        assert_eq!(status.health_state, HealthState::Operational);
+    }
+
+    #[test]
+    fn flips_held_back_by_thresholds_still_count() {
+        let mut status = project_status(2, 2);
+        for minute in 0..4 {
+            let health_state = if minute % 2 == 0 {
+                HealthState::MajorOutage
+            } else {
+                HealthState::Operational
+            };
+            status.record_report(health_state, at(minute));
+        }
+        assert!(status.is_healthy);
+        assert!(status.flapping);
+    }
+
+    #[test]
+    fn flapping_ends_with_the_window() {
+        let mut status = project_status(1, 1);
+        for minute in 0..4 {
+            let health_state = if minute % 2 == 0 {
+                HealthState::MajorOutage
+            } else {
+                HealthState::Operational
+            };
+            status.record_report(health_state, at(minute));
+        }
+        assert!(status.flapping);
+
+        status.record_report(HealthState::Operational, at(FLAP_WINDOW_MINUTES));
+        assert!(!status.flapping);
+    }
+
+    #[test]
+    fn slow_flips_are_not_flapping() {
+        let mut status = project_status(1, 1);
+        for step in 0..8 {
+            let health_state = if step % 2 == 0 {
+                HealthState::MajorOutage
+            } else {
+                HealthState::Operational
+            };
+            status.record_report(health_state, at(step * 5));
+            assert!(!status.flapping);
+        }
+    }
+
+    #[test]
+    fn set_health_state_bypasses_the_thresholds() {
+        let mut status = project_status(3, 3);
+        status.record_report(HealthState::MajorOutage, at(0));
+        status.set_health_state(HealthState::Maintenance);
+        assert!(!status.is_healthy);
+        assert_eq!(status.health_state, HealthState::Maintenance);
+        assert_eq!(status.pending_reports, 0);
+        assert!(!status.flapping);
+    }
+
+    #[test]
+    fn update_tells_cleared_check_fields_from_missing_ones() {
+        let update: ProjectStatusUpdate = serde_json::from_str(
+            r#"{"check_target": null, "check_timeout_ms": 500, "check_grpc_service": "api"}"#,
+        )
+        .unwrap();
+        assert_eq!(update.check_target, Some(None));
+        assert_eq!(update.check_timeout_ms, Some(Some(500)));
+        assert_eq!(update.check_grpc_service, Some(Some("api")));
+        assert_eq!(update.check_interval_secs, None);
+        assert_eq!(update.check_expected_body, None);
+    }
 }
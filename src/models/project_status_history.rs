@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use diesel::{
+    deserialize::{self, FromSql, FromSqlRow},
+    expression::AsExpression,
+    pg::{Pg, PgValue},
+    serialize::{self, IsNull, Output, ToSql},
+    Associations, Identifiable, Insertable, Queryable,
+};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+use super::{health_state::HealthState, project_status::ProjectStatus};
+use crate::schema::{project_status_history, sql_types};
+
+#[derive(Serialize, Deserialize, AsExpression, FromSqlRow, Clone, Copy, Debug, PartialEq, Eq)]
+#[diesel(sql_type = sql_types::ReportSource)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportSource {
+    Rest,
+    Grpc,
+    Prober,
+    Maintenance,
+}
+
+impl ToSql<sql_types::ReportSource, Pg> for ReportSource {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            ReportSource::Rest => out.write_all(b"rest")?,
+            ReportSource::Grpc => out.write_all(b"grpc")?,
+            ReportSource::Prober => out.write_all(b"prober")?,
+            ReportSource::Maintenance => out.write_all(b"maintenance")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::ReportSource, Pg> for ReportSource {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"rest" => Ok(ReportSource::Rest),
+            b"grpc" => Ok(ReportSource::Grpc),
+            b"prober" => Ok(ReportSource::Prober),
+            b"maintenance" => Ok(ReportSource::Maintenance),
+            _ => Err("Unrecognized report source".into()),
+        }
+    }
+}
+
+#[derive(Serialize, Queryable, Insertable, Identifiable, Associations, Clone, Debug)]
+#[diesel(belongs_to(ProjectStatus))]
+#[diesel(table_name = project_status_history)]
+#[diesel(primary_key(project_status_history_id))]
+pub struct ProjectStatusHistory {
+    pub project_status_history_id: String,
+    pub project_status_id: String,
+    pub old_state: HealthState,
+    pub new_state: HealthState,
+    pub source: ReportSource,
+    pub reporter: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,145 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use diesel::{Associations, Identifiable, Insertable, Queryable};
+use serde::Serialize;
+
+use super::project_status::ProjectStatus;
+use crate::schema::project_status_metrics;
+
+#[derive(Serialize, Queryable, Insertable, Identifiable, Associations, Clone, Debug)]
+#[diesel(belongs_to(ProjectStatus))]
+#[diesel(table_name = project_status_metrics)]
+#[diesel(primary_key(project_status_metric_id))]
+pub struct ProjectStatusMetric {
+    pub project_status_metric_id: String,
+    pub project_status_id: String,
+    pub latency_ms: Option<i32>,
+    pub detail: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Latency percentiles of the samples within `[start, start + bucket)`.
+#[derive(Serialize, Debug)]
+pub struct LatencyBucket {
+    pub start: DateTime<Utc>,
+    pub samples: usize,
+    pub p50: i32,
+    pub p95: i32,
+    pub p99: i32,
+}
+
+impl LatencyBucket {
+    /// Groups `metrics`, ordered by `created_at`, into buckets of `bucket` length
+    /// aligned to the Unix epoch. Samples without a latency are ignored and empty
+    /// buckets are left out.
+    pub fn aggregate(metrics: &[ProjectStatusMetric], bucket: Duration) -> Vec<Self> {
+        let bucket_secs = bucket.num_seconds().max(1);
+        let mut buckets: Vec<(i64, Vec<i32>)> = Vec::new();
+        for metric in metrics {
+            let latency_ms = match metric.latency_ms {
+                Some(latency_ms) => latency_ms,
+                None => continue,
+            };
+            let start = metric.created_at.timestamp().div_euclid(bucket_secs) * bucket_secs;
+            match buckets.last_mut() {
+                Some((last_start, latencies)) if *last_start == start => latencies.push(latency_ms),
+                _ => buckets.push((start, vec![latency_ms])),
+            }
+        }
+
+        buckets
+            .into_iter()
+            .filter_map(|(start, mut latencies)| {
+                latencies.sort_unstable();
+                Some(Self {
+                    start: Utc.timestamp_opt(start, 0).single()?,
+                    samples: latencies.len(),
+                    p50: percentile(&latencies, 50),
+                    p95: percentile(&latencies, 95),
+                    p99: percentile(&latencies, 99),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Nearest-rank percentile of sorted, non-empty `values`.
+fn percentile(values: &[i32], p: usize) -> i32 {
+    let rank = (p * values.len() + 99) / 100;
+    values[rank.clamp(1, values.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2023, 5, 1, 12, 0, 0).unwrap() + Duration::seconds(secs)
+    }
+
+    fn metric(secs: i64, latency_ms: Option<i32>) -> ProjectStatusMetric {
+        ProjectStatusMetric {
+            project_status_metric_id: format!("metric-{}", secs),
+            project_status_id: String::from("status"),
+            latency_ms,
+            detail: None,
+            created_at: at(secs),
+        }
+    }
+
+    #[test]
+    fn no_latencies_give_no_buckets() {
+        assert!(LatencyBucket::aggregate(&[], Duration::minutes(5)).is_empty());
+        let metrics = [metric(0, None), metric(1, None)];
+        assert!(LatencyBucket::aggregate(&metrics, Duration::minutes(5)).is_empty());
+    }
+
+    #[test]
+    fn one_sample_is_every_percentile() {
+        let buckets = LatencyBucket::aggregate(&[metric(30, Some(120))], Duration::minutes(5));
+        assert_eq!(buckets.len(), 1);
+        assert_eq!(buckets[0].start, at(0));
+        assert_eq!(buckets[0].samples, 1);
+        assert_eq!(
+            (buckets[0].p50, buckets[0].p95, buckets[0].p99),
+            (120, 120, 120)
+        );
+    }
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        let metrics = (1..=100)
+            .rev()
+            .map(|latency_ms| metric(0, Some(latency_ms)))
+            .collect::<Vec<_>>();
+        let buckets = LatencyBucket::aggregate(&metrics, Duration::minutes(5));
+        assert_eq!(buckets.len(), 1);
+        assert_eq!(buckets[0].samples, 100);
+        assert_eq!(
+            (buckets[0].p50, buckets[0].p95, buckets[0].p99),
+            (50, 95, 99)
+        );
+
+        let metrics = [10, 20, 30, 40].map(|latency_ms| metric(0, Some(latency_ms)));
+        let buckets = LatencyBucket::aggregate(&metrics, Duration::minutes(5));
+        assert_eq!(
+            (buckets[0].p50, buckets[0].p95, buckets[0].p99),
+            (20, 40, 40)
+        );
+    }
+
+    #[test]
+    fn samples_on_either_side_of_a_boundary_go_to_separate_buckets() {
+        let metrics = [
+            metric(0, Some(10)),
+            metric(299, Some(30)),
+            metric(300, Some(200)),
+            metric(301, None),
+        ];
+        let buckets = LatencyBucket::aggregate(&metrics, Duration::minutes(5));
+        assert_eq!(buckets.len(), 2);
+        assert_eq!((buckets[0].start, buckets[0].samples), (at(0), 2));
+        assert_eq!((buckets[0].p50, buckets[0].p99), (10, 30));
+        assert_eq!((buckets[1].start, buckets[1].samples), (at(300), 1));
+        assert_eq!(buckets[1].p50, 200);
+    }
+}
@@ -0,0 +1,174 @@
+use diesel::{
+    deserialize::{self, FromSql, FromSqlRow},
+    expression::AsExpression,
+    pg::{Pg, PgValue},
+    serialize::{self, IsNull, Output, ToSql},
+};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+use super::{health_state::HealthState, project_status::ProjectStatus};
+use crate::schema::sql_types;
+
+/// Overall health of a project, rolled up from its statuses.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectHealth {
+    Healthy,
+    Degraded,
+    Down,
+    /// No status has reported a state that counts towards the rollup.
+    Unknown,
+}
+
+/// How a project's statuses are combined into its `ProjectHealth`. Statuses under
+/// maintenance or in an unknown state are left out.
+#[derive(
+    Serialize, Deserialize, AsExpression, FromSqlRow, Clone, Copy, Debug, Default, PartialEq, Eq,
+)]
+#[diesel(sql_type = sql_types::RollupPolicy)]
+#[serde(rename_all = "snake_case")]
+pub enum RollupPolicy {
+    /// The project is as healthy as its least healthy status.
+    #[default]
+    Worst,
+    /// The project takes the state held by more than half of its statuses, counting
+    /// a down status as degraded too.
+    Majority,
+    /// The project is as healthy as its healthiest status.
+    Best,
+}
+
+impl RollupPolicy {
+    pub fn apply(self, project_statuses: &[ProjectStatus]) -> ProjectHealth {
+        let (mut healthy, mut degraded, mut down) = (0, 0, 0);
+        for project_status in project_statuses {
+            match project_status.health_state {
+                HealthState::Operational => healthy += 1,
+                HealthState::Degraded | HealthState::PartialOutage => degraded += 1,
+                HealthState::MajorOutage => down += 1,
+                HealthState::Maintenance | HealthState::Unknown => {}
+            }
+        }
+        let counted = healthy + degraded + down;
+        if counted == 0 {
+            return ProjectHealth::Unknown;
+        }
+
+        match self {
+            RollupPolicy::Worst if down > 0 => ProjectHealth::Down,
+            RollupPolicy::Worst if degraded > 0 => ProjectHealth::Degraded,
+            RollupPolicy::Worst => ProjectHealth::Healthy,
+            RollupPolicy::Majority if down * 2 > counted => ProjectHealth::Down,
+            RollupPolicy::Majority if (down + degraded) * 2 > counted => ProjectHealth::Degraded,
+            RollupPolicy::Majority => ProjectHealth::Healthy,
+            RollupPolicy::Best if healthy > 0 => ProjectHealth::Healthy,
+            RollupPolicy::Best if degraded > 0 => ProjectHealth::Degraded,
+            RollupPolicy::Best => ProjectHealth::Down,
+        }
+    }
+}
+
+impl ToSql<sql_types::RollupPolicy, Pg> for RollupPolicy {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            RollupPolicy::Worst => out.write_all(b"worst")?,
+            RollupPolicy::Majority => out.write_all(b"majority")?,
+            RollupPolicy::Best => out.write_all(b"best")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::RollupPolicy, Pg> for RollupPolicy {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"worst" => Ok(RollupPolicy::Worst),
+            b"majority" => Ok(RollupPolicy::Majority),
+            b"best" => Ok(RollupPolicy::Best),
+            _ => Err("Unrecognized rollup policy".into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::serde::json::{json, serde_json};
+
+    use super::*;
+
+    fn statuses(health_states: &[HealthState]) -> Vec<ProjectStatus> {
+        health_states
+            .iter()
+            .enumerate()
+            .map(|(i, health_state)| {
+                serde_json::from_value(json!({
+                    "project_status_id": format!("status-{}", i),
+                    "name": format!("status-{}", i),
+                    "is_healthy": health_state.is_healthy(),
+                    "project_id": "project",
+                    "health_state": health_state,
+                }))
+                .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn no_counted_status_is_unknown() {
+        let statuses = statuses(&[HealthState::Maintenance, HealthState::Unknown]);
+        assert_eq!(RollupPolicy::Worst.apply(&statuses), ProjectHealth::Unknown);
+        assert_eq!(RollupPolicy::Best.apply(&[]), ProjectHealth::Unknown);
+    }
+
+    #[test]
+    fn worst_takes_the_least_healthy_status() {
+        let statuses = statuses(&[
+            HealthState::Operational,
+            HealthState::PartialOutage,
+            HealthState::Maintenance,
+        ]);
+        assert_eq!(
+            RollupPolicy::Worst.apply(&statuses),
+            ProjectHealth::Degraded
+        );
+
+        let statuses = self::statuses(&[HealthState::Operational, HealthState::MajorOutage]);
+        assert_eq!(RollupPolicy::Worst.apply(&statuses), ProjectHealth::Down);
+    }
+
+    #[test]
+    fn best_takes_the_healthiest_status() {
+        let statuses = statuses(&[HealthState::MajorOutage, HealthState::Degraded]);
+        assert_eq!(RollupPolicy::Best.apply(&statuses), ProjectHealth::Degraded);
+
+        let statuses = self::statuses(&[HealthState::MajorOutage, HealthState::Unknown]);
+        assert_eq!(RollupPolicy::Best.apply(&statuses), ProjectHealth::Down);
+    }
+
+    #[test]
+    fn majority_counts_down_statuses_as_degraded_too() {
+        let statuses = statuses(&[
+            HealthState::Operational,
+            HealthState::Degraded,
+            HealthState::MajorOutage,
+        ]);
+        assert_eq!(
+            RollupPolicy::Majority.apply(&statuses),
+            ProjectHealth::Degraded
+        );
+
+        let statuses = self::statuses(&[
+            HealthState::Operational,
+            HealthState::MajorOutage,
+            HealthState::MajorOutage,
+        ]);
+        assert_eq!(RollupPolicy::Majority.apply(&statuses), ProjectHealth::Down);
+
+        let statuses = self::statuses(&[HealthState::Operational, HealthState::MajorOutage]);
+        assert_eq!(
+            RollupPolicy::Majority.apply(&statuses),
+            ProjectHealth::Healthy
+        );
+    }
+}
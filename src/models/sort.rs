@@ -0,0 +1,17 @@
+/// Order of list endpoints; a leading `-` sorts in descending order.
+#[derive(FromFormField, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Sort {
+    #[default]
+    #[field(value = "created_at")]
+    CreatedAt,
+    #[field(value = "-created_at")]
+    CreatedAtDesc,
+    #[field(value = "updated_at")]
+    UpdatedAt,
+    #[field(value = "-updated_at")]
+    UpdatedAtDesc,
+    #[field(value = "name")]
+    Name,
+    #[field(value = "-name")]
+    NameDesc,
+}
@@ -0,0 +1,19 @@
+use super::{
+    incident::Incident, project::Project, project_status::ProjectStatus, rollup::ProjectHealth,
+};
+
+/// A status as shown on the status page, with its daily uptime, oldest first.
+#[derive(Debug)]
+pub struct StatusPageStatus {
+    pub project_status: ProjectStatus,
+    pub daily_uptime: Vec<Option<f64>>,
+}
+
+/// A project as shown on the status page.
+#[derive(Debug)]
+pub struct StatusPageProject {
+    pub project: Project,
+    pub health: ProjectHealth,
+    pub project_statuses: Vec<StatusPageStatus>,
+    pub incidents: Vec<Incident>,
+}
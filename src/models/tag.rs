@@ -0,0 +1,92 @@
+use diesel::{AsChangeset, Associations, Identifiable, Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use validator::ValidationError;
+use validator_derive::Validate;
+
+use super::project::Project;
+use crate::schema::{project_tags, tags};
+
+#[derive(Serialize, Queryable, Insertable, AsChangeset, Identifiable, Clone, Debug)]
+#[diesel(table_name = tags)]
+#[diesel(primary_key(tag_id))]
+pub struct Tag {
+    pub tag_id: String,
+    pub name: String,
+}
+
+impl Tag {
+    /// Tag names are case-insensitive, so `Rust` and `rust` are the same tag.
+    pub fn normalize(name: &str) -> String {
+        name.trim().to_lowercase()
+    }
+}
+
+/// Checks that `name` is 1 to 32 characters long once normalized, as it's stored.
+pub fn validate_name(name: &str) -> Result<(), ValidationError> {
+    let length = Tag::normalize(name).chars().count();
+    if (1..=32).contains(&length) {
+        return Ok(());
+    }
+    let mut error = ValidationError::new("length");
+    error.message = Some(Cow::from("Name must be between 1 and 32 characters"));
+    Err(error)
+}
+
+/// Checks every name in `names` like `validate_name`.
+pub fn validate_names(names: &[String]) -> Result<(), ValidationError> {
+    names.iter().try_for_each(|name| {
+        validate_name(name).map_err(|mut error| {
+            error.message = Some(Cow::from("Tag names must be between 1 and 32 characters"));
+            error
+        })
+    })
+}
+
+#[derive(Queryable, Insertable, Identifiable, Associations, Clone, Debug)]
+#[diesel(belongs_to(Project))]
+#[diesel(belongs_to(Tag))]
+#[diesel(table_name = project_tags)]
+#[diesel(primary_key(project_id, tag_id))]
+pub struct ProjectTag {
+    pub project_id: String,
+    pub tag_id: String,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct TagCreate<'a> {
+    #[validate(custom = "validate_name")]
+    pub name: &'a str,
+}
+
+#[derive(Deserialize, Validate, Debug)]
+#[serde(crate = "rocket::serde")]
+pub struct TagUpdate<'a> {
+    #[validate(custom = "validate_name")]
+    pub name: Option<&'a str>,
+}
+
+#[cfg(test)]
+mod tests {
+    use validator::Validate;
+
+    use super::*;
+
+    #[test]
+    fn names_are_checked_once_normalized() {
+        assert!(validate_name("Rust").is_ok());
+        assert!(validate_name(&format!("  {}  ", "a".repeat(32))).is_ok());
+        assert!(validate_name("   ").is_err());
+        assert!(validate_name(&"a".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn whitespace_only_tags_are_rejected_everywhere() {
+        assert!(TagCreate { name: " \t " }.validate().is_err());
+        assert!(TagUpdate { name: Some("  ") }.validate().is_err());
+        assert!(TagUpdate { name: None }.validate().is_ok());
+        assert!(validate_names(&[String::from("rust"), String::from(" ")]).is_err());
+        assert!(validate_names(&[String::from("rust"), String::from("Web ")]).is_ok());
+    }
+}
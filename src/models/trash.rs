@@ -0,0 +1,10 @@
+use serde::Serialize;
+
+use super::{project::Project, project_status::ProjectStatus};
+
+/// Deleted projects and statuses that can still be restored.
+#[derive(Serialize, Debug)]
+pub struct Trash {
+    pub projects: Vec<Project>,
+    pub project_statuses: Vec<ProjectStatus>,
+}
@@ -0,0 +1,239 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use super::{health_state::HealthState, project_status_history::ProjectStatusHistory};
+
+/// Longest window uptime is reported for.
+pub const MAX_WINDOW_DAYS: i64 = 90;
+
+/// Uptime percentages over rolling windows. Time spent in maintenance or in an
+/// unknown state is left out; a window with no other time has no uptime.
+#[derive(Serialize, Clone, Copy, Debug, Default)]
+pub struct Uptime {
+    #[serde(rename = "24h")]
+    pub last_24h: Option<f64>,
+    #[serde(rename = "7d")]
+    pub last_7d: Option<f64>,
+    #[serde(rename = "30d")]
+    pub last_30d: Option<f64>,
+    #[serde(rename = "90d")]
+    pub last_90d: Option<f64>,
+}
+
+impl Uptime {
+    /// `history` must be ordered by `created_at` and cover the last `MAX_WINDOW_DAYS`.
+    pub fn compute(
+        current: HealthState,
+        history: &[ProjectStatusHistory],
+        now: DateTime<Utc>,
+    ) -> Self {
+        let window = |days| percentage(current, history, now - Duration::days(days), now);
+        Self {
+            last_24h: window(1),
+            last_7d: window(7),
+            last_30d: window(30),
+            last_90d: window(MAX_WINDOW_DAYS),
+        }
+    }
+
+    /// Uptime of each of the last `MAX_WINDOW_DAYS` 24 hour periods, oldest first.
+    pub fn daily(
+        current: HealthState,
+        history: &[ProjectStatusHistory],
+        now: DateTime<Utc>,
+    ) -> Vec<Option<f64>> {
+        (0..MAX_WINDOW_DAYS)
+            .rev()
+            .map(|days_ago| {
+                let to = now - Duration::days(days_ago);
+                percentage(
+                    current_at(current, history, to),
+                    history,
+                    to - Duration::days(1),
+                    to,
+                )
+            })
+            .collect()
+    }
+
+    /// Averages the uptime of several statuses, skipping those without one.
+    pub fn average(uptimes: &[Uptime]) -> Self {
+        let average = |window: fn(&Uptime) -> Option<f64>| {
+            let values = uptimes.iter().filter_map(window).collect::<Vec<f64>>();
+            (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+        };
+        Self {
+            last_24h: average(|u| u.last_24h),
+            last_7d: average(|u| u.last_7d),
+            last_30d: average(|u| u.last_30d),
+            last_90d: average(|u| u.last_90d),
+        }
+    }
+}
+
+/// The state at `at`, given the state now and the transitions since.
+fn current_at(
+    current: HealthState,
+    history: &[ProjectStatusHistory],
+    at: DateTime<Utc>,
+) -> HealthState {
+    history
+        .iter()
+        .find(|h| h.created_at > at)
+        .map_or(current, |h| h.old_state)
+}
+
+fn percentage(
+    current: HealthState,
+    history: &[ProjectStatusHistory],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Option<f64> {
+    let transitions = history
+        .iter()
+        .filter(|h| h.created_at > from && h.created_at <= to)
+        .collect::<Vec<&ProjectStatusHistory>>();
+
+    let mut state = transitions.first().map_or(current, |h| h.old_state);
+    let mut since = from;
+    let mut up = Duration::zero();
+    let mut counted = Duration::zero();
+    let mut accumulate = |state: HealthState, duration: Duration| match state {
+        HealthState::Maintenance | HealthState::Unknown => {}
+        state => {
+            counted = counted + duration;
+            if state.is_healthy() {
+                up = up + duration;
+            }
+        }
+    };
+    for transition in transitions {
+        accumulate(state, transition.created_at - since);
+        state = transition.new_state;
+        since = transition.created_at;
+    }
+    accumulate(state, to - since);
+
+    (counted > Duration::zero())
+        .then(|| 100.0 * up.num_milliseconds() as f64 / counted.num_milliseconds() as f64)
+}
+
+#[derive(Serialize, Debug)]
+pub struct StatusUptime {
+    pub project_status_id: String,
+    pub name: String,
+    pub uptime: Uptime,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ProjectUptime {
+    pub project_id: String,
+    pub uptime: Uptime,
+    pub project_statuses: Vec<StatusUptime>,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::models::project_status_history::ReportSource;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2023, 5, 1, 12, 0, 0).unwrap()
+    }
+
+    fn transition(
+        hours_ago: i64,
+        old_state: HealthState,
+        new_state: HealthState,
+    ) -> ProjectStatusHistory {
+        ProjectStatusHistory {
+            project_status_history_id: format!("history-{}", hours_ago),
+            project_status_id: String::from("status"),
+            old_state,
+            new_state,
+            source: ReportSource::Rest,
+            reporter: None,
+            created_at: now() - Duration::hours(hours_ago),
+        }
+    }
+
+    #[test]
+    fn steady_state_is_fully_up_or_down() {
+        let up = Uptime::compute(HealthState::Operational, &[], now());
+        assert_eq!(up.last_24h, Some(100.0));
+        assert_eq!(up.last_90d, Some(100.0));
+
+        let down = Uptime::compute(HealthState::MajorOutage, &[], now());
+        assert_eq!(down.last_24h, Some(0.0));
+        assert_eq!(down.last_90d, Some(0.0));
+    }
+
+    #[test]
+    fn degraded_counts_as_up() {
+        let uptime = Uptime::compute(HealthState::Degraded, &[], now());
+        assert_eq!(uptime.last_7d, Some(100.0));
+    }
+
+    #[test]
+    fn outage_counts_against_each_window() {
+        let history = [transition(
+            12,
+            HealthState::Operational,
+            HealthState::MajorOutage,
+        )];
+        let uptime = Uptime::compute(HealthState::MajorOutage, &history, now());
+        assert_eq!(uptime.last_24h, Some(50.0));
+        assert_eq!(uptime.last_7d, Some(100.0 * 156.0 / 168.0));
+    }
+
+    #[test]
+    fn maintenance_and_unknown_time_is_left_out() {
+        let history = [transition(
+            12,
+            HealthState::MajorOutage,
+            HealthState::Maintenance,
+        )];
+        let uptime = Uptime::compute(HealthState::Maintenance, &history, now());
+        assert_eq!(uptime.last_24h, Some(0.0));
+
+        let uptime = Uptime::compute(HealthState::Unknown, &[], now());
+        assert_eq!(uptime.last_24h, None);
+        assert_eq!(uptime.last_90d, None);
+    }
+
+    #[test]
+    fn daily_splits_an_outage_across_days() {
+        let history = [
+            transition(36, HealthState::Operational, HealthState::MajorOutage),
+            transition(12, HealthState::MajorOutage, HealthState::Operational),
+        ];
+        let daily = Uptime::daily(HealthState::Operational, &history, now());
+        assert_eq!(daily.len(), MAX_WINDOW_DAYS as usize);
+        assert_eq!(daily[daily.len() - 1], Some(50.0));
+        assert_eq!(daily[daily.len() - 2], Some(50.0));
+        assert_eq!(daily[daily.len() - 3], Some(100.0));
+        assert_eq!(daily[0], Some(100.0));
+    }
+
+    #[test]
+    fn average_skips_statuses_without_uptime() {
+        let uptimes = [
+            Uptime {
+                last_24h: Some(100.0),
+                last_7d: None,
+                ..Default::default()
+            },
+            Uptime {
+                last_24h: Some(50.0),
+                last_7d: None,
+                ..Default::default()
+            },
+            Uptime::default(),
+        ];
+        let average = Uptime::average(&uptimes);
+        assert_eq!(average.last_24h, Some(75.0));
+        assert_eq!(average.last_7d, None);
+    }
+}
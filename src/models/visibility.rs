@@ -0,0 +1,70 @@
+use diesel::{
+    deserialize::{self, FromSql, FromSqlRow},
+    expression::AsExpression,
+    pg::{Pg, PgValue},
+    serialize::{self, IsNull, Output, ToSql},
+};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+use crate::schema::sql_types;
+
+/// Who gets to see a project. Authenticated requests see every project regardless.
+#[derive(
+    Serialize,
+    Deserialize,
+    FromFormField,
+    AsExpression,
+    FromSqlRow,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+)]
+#[diesel(sql_type = sql_types::Visibility)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    Draft,
+    Unlisted,
+    #[default]
+    Published,
+    Archived,
+}
+
+impl Visibility {
+    /// Listed, searchable and part of feeds, the status page and the event stream.
+    pub fn is_listed(self) -> bool {
+        self == Visibility::Published
+    }
+
+    /// Reachable by anyone who knows the project's id, slug or name.
+    pub fn is_public(self) -> bool {
+        matches!(self, Visibility::Published | Visibility::Unlisted)
+    }
+}
+
+impl ToSql<sql_types::Visibility, Pg> for Visibility {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            Visibility::Draft => out.write_all(b"draft")?,
+            Visibility::Unlisted => out.write_all(b"unlisted")?,
+            Visibility::Published => out.write_all(b"published")?,
+            Visibility::Archived => out.write_all(b"archived")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::Visibility, Pg> for Visibility {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"draft" => Ok(Visibility::Draft),
+            b"unlisted" => Ok(Visibility::Unlisted),
+            b"published" => Ok(Visibility::Published),
+            b"archived" => Ok(Visibility::Archived),
+            _ => Err("Unrecognized visibility".into()),
+        }
+    }
+}
@@ -0,0 +1,342 @@
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use std::borrow::Cow;
+
+use crate::{
+    db::{project_recipients, project_statuses, projects, Db},
+    errors::DbError,
+    models::{health_state::HealthState, project_status::ProjectStatus},
+};
+
+pub const DEFAULT_UNHEALTHY_SUBJECT: &str = "[{{project}}] is unhealthy";
+pub const DEFAULT_UNHEALTHY_BODY: &str =
+    "{{project}} ({{url}}) is unhealthy.\n\nUnhealthy statuses: {{statuses}}";
+pub const DEFAULT_RECOVERED_SUBJECT: &str = "[{{project}}] has recovered";
+pub const DEFAULT_RECOVERED_BODY: &str = "{{project}} ({{url}}) is healthy again.";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HealthTransition {
+    Unhealthy,
+    Recovered,
+}
+
+/// Subject and body with `{{project}}`, `{{url}}` and `{{statuses}}` placeholders.
+#[derive(Clone, Debug)]
+pub struct Template {
+    subject: String,
+    body: String,
+}
+
+impl Template {
+    pub fn new<T: Into<String>>(subject: T, body: T) -> Self {
+        Self {
+            subject: subject.into(),
+            body: body.into(),
+        }
+    }
+
+    pub fn render(&self, vars: &[(&str, &str)]) -> (String, String) {
+        let render = |text: &str| {
+            vars.iter().fold(text.to_string(), |text, (key, value)| {
+                text.replace(&format!("{{{{{key}}}}}"), value)
+            })
+        };
+        (render(&self.subject), render(&self.body))
+    }
+}
+
+pub struct SmtpSettings {
+    pub host: String,
+    pub port: u16,
+    pub starttls: bool,
+    pub credentials: Option<(String, String)>,
+    pub from: String,
+    pub unhealthy: Template,
+    pub recovered: Template,
+}
+
+#[derive(Clone)]
+struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    unhealthy: Template,
+    recovered: Template,
+}
+
+/// Emails project recipients when a project goes unhealthy and when it recovers.
+/// Disabled when no SMTP settings are given.
+#[derive(Clone)]
+pub struct Notifier(Option<SmtpMailer>);
+
+impl Notifier {
+    pub fn new(settings: Option<SmtpSettings>) -> Result<Self, String> {
+        let settings = match settings {
+            Some(settings) => settings,
+            None => return Ok(Self(None)),
+        };
+
+        if !settings.starttls && settings.credentials.is_some() {
+            return Err(String::from(
+                "SMTP credentials would be sent in cleartext, set SMTP_STARTTLS=true",
+            ));
+        }
+
+        let builder = if settings.starttls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&settings.host)
+                .map_err(|e| e.to_string())?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&settings.host)
+        };
+        let mut builder = builder.port(settings.port);
+        if let Some((username, password)) = settings.credentials {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        let from = settings
+            .from
+            .parse::<Mailbox>()
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self(Some(SmtpMailer {
+            transport: builder.build(),
+            from,
+            unhealthy: settings.unhealthy,
+            recovered: settings.recovered,
+        })))
+    }
+
+    /// Compares the project's health before and after `updated` replaced `previous`
+    /// and emails the project's recipients if the overall health flipped. Statuses
+    /// under maintenance or in an unknown state count as healthy and moving into
+    /// either, or out of maintenance, notifies nobody, so planned downtime sends no
+    /// alerts while an outage outlasting it does once the next report comes in.
+    pub async fn notify_transition(
+        &self,
+        db: &Db,
+        previous: &ProjectStatus,
+        updated: &ProjectStatus,
+    ) {
+        let mailer = match &self.0 {
+            Some(mailer) => mailer,
+            None => return,
+        };
+        if previous.health_state == HealthState::Maintenance
+            || matches!(
+                updated.health_state,
+                HealthState::Maintenance | HealthState::Unknown
+            )
+            || is_up(previous) == is_up(updated)
+        {
+            return;
+        }
+
+        let project_id = Cow::Owned(updated.project_id.clone());
+        let result = db
+            .run(move |conn| {
+                let project = projects::find_by_id(conn, &project_id)?;
+                let statuses = project_statuses::find_by_project(conn, &project_id)?;
+                let recipients = project_recipients::find_by_project(conn, &project_id)?;
+                Ok::<_, DbError<'static>>((project, statuses, recipients))
+            })
+            .await;
+        let (project, statuses, recipients) = match result {
+            Ok((Some(project), statuses, recipients)) => (project, statuses, recipients),
+            Ok((None, _, _)) => return,
+            Err(e) => {
+                error!("Unable to load notification data: {}", e);
+                return;
+            }
+        };
+
+        let is_healthy = statuses.iter().all(is_up);
+        let was_healthy = statuses.iter().all(|s| {
+            if s.project_status_id == previous.project_status_id {
+                is_up(previous)
+            } else {
+                is_up(s)
+            }
+        });
+        let transition = match (was_healthy, is_healthy) {
+            (true, false) => HealthTransition::Unhealthy,
+            (false, true) => HealthTransition::Recovered,
+            _ => return,
+        };
+
+        let unhealthy_statuses = statuses
+            .iter()
+            .filter(|s| !is_up(s))
+            .map(|s| s.name.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ");
+        let (subject, body) = mailer.template(transition).render(&[
+            ("project", project.name.as_str()),
+            ("url", project.url.as_str()),
+            ("statuses", unhealthy_statuses.as_str()),
+        ]);
+        let recipients = recipients
+            .into_iter()
+            .map(|r| r.email)
+            .collect::<Vec<String>>();
+
+        let mailer = mailer.clone();
+        tokio::spawn(async move { mailer.send(recipients, subject, body).await });
+    }
+}
+
+fn is_up(project_status: &ProjectStatus) -> bool {
+    project_status.is_healthy
+        || matches!(
+            project_status.health_state,
+            HealthState::Maintenance | HealthState::Unknown
+        )
+}
+
+impl SmtpMailer {
+    fn template(&self, transition: HealthTransition) -> &Template {
+        match transition {
+            HealthTransition::Unhealthy => &self.unhealthy,
+            HealthTransition::Recovered => &self.recovered,
+        }
+    }
+
+    async fn send(&self, recipients: Vec<String>, subject: String, body: String) {
+        for recipient in recipients {
+            let to = match recipient.parse::<Mailbox>() {
+                Ok(to) => to,
+                Err(e) => {
+                    error!("Invalid recipient '{}': {}", recipient, e);
+                    continue;
+                }
+            };
+            let message = Message::builder()
+                .from(self.from.clone())
+                .to(to)
+                .subject(subject.as_str())
+                .header(ContentType::TEXT_PLAIN)
+                .body(body.clone());
+            let message = match message {
+                Ok(message) => message,
+                Err(e) => {
+                    error!("Unable to build email for '{}': {}", recipient, e);
+                    continue;
+                }
+            };
+            if let Err(e) = self.transport.send(message).await {
+                error!("Unable to send email to '{}': {}", recipient, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+        sync::oneshot,
+    };
+
+    use super::*;
+
+    #[test]
+    fn render_fills_in_every_placeholder() {
+        let template = Template::new(
+            "[{{project}}] is down",
+            "{{project}} ({{url}}): {{statuses}}",
+        );
+        let (subject, body) = template.render(&[
+            ("project", "crate"),
+            ("url", "https://example.com"),
+            ("statuses", "api, web"),
+        ]);
+        assert_eq!(subject, "[crate] is down");
+        assert_eq!(body, "crate (https://example.com): api, web");
+    }
+
+    #[test]
+    fn render_leaves_unknown_placeholders_alone() {
+        let template = Template::new("{{project}} {{other}}", "{project}");
+        let (subject, body) = template.render(&[("project", "crate")]);
+        assert_eq!(subject, "crate {{other}}");
+        assert_eq!(body, "{project}");
+    }
+
+    fn smtp_settings(port: u16) -> SmtpSettings {
+        SmtpSettings {
+            host: String::from("127.0.0.1"),
+            port,
+            starttls: false,
+            credentials: None,
+            from: String::from("portfolio@localhost"),
+            unhealthy: Template::new(DEFAULT_UNHEALTHY_SUBJECT, DEFAULT_UNHEALTHY_BODY),
+            recovered: Template::new(DEFAULT_RECOVERED_SUBJECT, DEFAULT_RECOVERED_BODY),
+        }
+    }
+
+    #[test]
+    fn credentials_require_starttls() {
+        let settings = SmtpSettings {
+            credentials: Some((String::from("user"), String::from("secret"))),
+            ..smtp_settings(25)
+        };
+        assert!(Notifier::new(Some(settings)).is_err());
+    }
+
+    /// Accepts one SMTP session on a local port, answering every command positively,
+    /// and sends back the message it was given.
+    async fn smtp_sink() -> (u16, oneshot::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (sender, receiver) = oneshot::channel::<String>();
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = socket.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            writer.write_all(b"220 sink ESMTP\r\n").await.unwrap();
+            let mut data: Option<String> = None;
+            while let Some(line) = lines.next_line().await.unwrap() {
+                if let Some(message) = data.as_mut() {
+                    if line == "." {
+                        writer.write_all(b"250 OK\r\n").await.unwrap();
+                        let _ = sender.send(std::mem::take(message));
+                        return;
+                    }
+                    message.push_str(&line);
+                    message.push('\n');
+                } else if line == "DATA" {
+                    writer.write_all(b"354 Go ahead\r\n").await.unwrap();
+                    data = Some(String::new());
+                } else {
+                    writer.write_all(b"250 OK\r\n").await.unwrap();
+                }
+            }
+        });
+        (port, receiver)
+    }
+
+    #[tokio::test]
+    async fn send_delivers_the_rendered_email() {
+        let (port, message) = smtp_sink().await;
+        let notifier = Notifier::new(Some(smtp_settings(port))).unwrap();
+        let mailer = notifier.0.unwrap();
+        let (subject, body) = mailer.template(HealthTransition::Unhealthy).render(&[
+            ("project", "crate"),
+            ("url", "https://example.com"),
+            ("statuses", "api"),
+        ]);
+
+        mailer
+            .send(vec![String::from("ops@example.com")], subject, body)
+            .await;
+
+        let message = message.await.unwrap();
+        assert!(message.contains("From: portfolio@localhost"));
+        assert!(message.contains("To: ops@example.com"));
+        assert!(message.contains("Subject: [crate] is unhealthy"));
+        assert!(message.contains("crate (https://example.com) is unhealthy."));
+        assert!(message.contains("Unhealthy statuses: api"));
+    }
+}
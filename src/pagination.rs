@@ -0,0 +1,151 @@
+use rocket::{
+    http::RawStr,
+    response::{self, Responder},
+    serde::json::Json,
+    Request,
+};
+use serde::Serialize;
+
+use crate::errors::CustomError;
+
+/// Page size used when none is given.
+pub const DEFAULT_LIMIT: u32 = 50;
+/// Largest page size a client can ask for.
+pub const MAX_LIMIT: u32 = 200;
+
+/// A window of `limit` rows starting at `offset`. This is offset pagination: the
+/// `next_cursor` clients pass back is the offset of the next page, so rows added or
+/// removed before it between two requests shift the following pages by as many rows.
+#[derive(Clone, Copy, Debug)]
+pub struct PageRequest {
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl PageRequest {
+    pub fn new(limit: Option<u32>, cursor: Option<&str>) -> Result<Self, CustomError<'_>> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let offset = match cursor {
+            Some(cursor) => cursor
+                .parse::<u32>()
+                .map_err(|_| CustomError::InvalidCursor(cursor))?,
+            None => 0,
+        };
+        Ok(Self {
+            limit: limit as i64,
+            offset: offset as i64,
+        })
+    }
+
+    /// Rows to fetch: one more than the page holds, to know whether a next page exists.
+    pub fn fetch_limit(&self) -> i64 {
+        self.limit + 1
+    }
+}
+
+/// A page of a list, answered as a JSON array with the cursor of the next page, if
+/// any, in the `X-Next-Cursor` header and a `Link: <..>; rel="next"` header.
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// Builds the page out of rows fetched with `PageRequest::fetch_limit`.
+    pub fn new(mut items: Vec<T>, page: PageRequest) -> Self {
+        let next_cursor = if items.len() as i64 > page.limit {
+            items.truncate(page.limit as usize);
+            Some((page.offset + page.limit).to_string())
+        } else {
+            None
+        };
+        Self { items, next_cursor }
+    }
+}
+
+impl<'r, T: Serialize> Responder<'r, 'static> for Page<T> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = Json(self.items).respond_to(req)?;
+        if let Some(cursor) = self.next_cursor {
+            response.set_raw_header(
+                "Link",
+                format!("<{}>; rel=\"next\"", next_link(req, &cursor)),
+            );
+            response.set_raw_header("X-Next-Cursor", cursor);
+        }
+        Ok(response)
+    }
+}
+
+/// The request's URI with `cursor` swapped for the next one.
+fn next_link(req: &Request<'_>, cursor: &str) -> String {
+    let mut query = req
+        .uri()
+        .query()
+        .map(|query| {
+            query
+                .segments()
+                .filter(|(name, _)| *name != "cursor")
+                .map(|(name, value)| {
+                    format!(
+                        "{}={}",
+                        RawStr::new(name).percent_encode(),
+                        RawStr::new(value).percent_encode()
+                    )
+                })
+                .collect::<Vec<String>>()
+        })
+        .unwrap_or_default();
+    query.push(format!("cursor={}", RawStr::new(cursor).percent_encode()));
+    format!("{}?{}", req.uri().path(), query.join("&"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_request_defaults_to_the_first_page() {
+        let page = PageRequest::new(None, None).unwrap();
+        assert_eq!(page.limit, i64::from(DEFAULT_LIMIT));
+        assert_eq!(page.offset, 0);
+        assert_eq!(page.fetch_limit(), i64::from(DEFAULT_LIMIT) + 1);
+    }
+
+    #[test]
+    fn page_request_clamps_the_limit() {
+        assert_eq!(PageRequest::new(Some(0), None).unwrap().limit, 1);
+        assert_eq!(
+            PageRequest::new(Some(MAX_LIMIT + 1), None).unwrap().limit,
+            i64::from(MAX_LIMIT)
+        );
+    }
+
+    #[test]
+    fn page_request_reads_the_cursor() {
+        assert_eq!(PageRequest::new(None, Some("20")).unwrap().offset, 20);
+        for cursor in ["", "-1", "abc", "1.5"] {
+            assert!(matches!(
+                PageRequest::new(None, Some(cursor)),
+                Err(CustomError::InvalidCursor(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn page_keeps_the_extra_row_for_the_next_cursor() {
+        let request = PageRequest::new(Some(3), Some("6")).unwrap();
+        let page = Page::new(vec![1, 2, 3, 4], request);
+        assert_eq!(page.items, vec![1, 2, 3]);
+        assert_eq!(page.next_cursor.as_deref(), Some("9"));
+    }
+
+    #[test]
+    fn last_page_has_no_next_cursor() {
+        let request = PageRequest::new(Some(3), Some("6")).unwrap();
+        let page = Page::new(vec![1, 2, 3], request);
+        assert_eq!(page.items, vec![1, 2, 3]);
+        assert_eq!(page.next_cursor, None);
+    }
+}
@@ -0,0 +1,337 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::{
+    net::TcpStream,
+    task::JoinHandle,
+    time::{sleep_until, timeout, Instant},
+};
+
+use crate::{
+    db::{project_statuses, Db},
+    grpc::health::health_proto::{
+        health_check_response::ServingStatus, health_client::HealthClient, HealthCheckRequest,
+    },
+    models::{
+        health_state::HealthState,
+        project::Project,
+        project_status::{CheckKind, ProjectStatus},
+        project_status_history::ReportSource,
+    },
+    reports::{Measurement, Report, Reporter, Reports},
+};
+
+/// The shortest the prober sleeps between two rounds.
+const MIN_SLEEP: Duration = Duration::from_secs(1);
+/// The longest the prober sleeps between two rounds, which is how long new and edited
+/// checks can take to be picked up.
+const MAX_SLEEP: Duration = Duration::from_secs(30);
+
+/// Defaults for checks that don't set their own interval or timeout.
+pub struct ProberSettings {
+    pub interval: Duration,
+    pub timeout: Duration,
+}
+
+/// Runs the check of every status with `check_enabled` set once its interval has
+/// elapsed and reports the outcome like any other health report. Each check runs in
+/// its own task, so a slow check doesn't hold up the others.
+pub struct Prober {
+    checker: Checker,
+    interval: Duration,
+    last_checked: HashMap<String, Instant>,
+    running: HashMap<String, JoinHandle<()>>,
+}
+
+/// Runs a single check and saves its outcome.
+#[derive(Clone)]
+struct Checker {
+    db: Arc<Db>,
+    client: reqwest::Client,
+    reports: Reports,
+    timeout: Duration,
+}
+
+impl Prober {
+    pub fn new(db: Db, reports: Reports, settings: &ProberSettings) -> Self {
+        Self {
+            checker: Checker {
+                db: Arc::new(db),
+                client: reqwest::Client::new(),
+                reports,
+                timeout: settings.timeout,
+            },
+            interval: settings.interval,
+            last_checked: HashMap::new(),
+            running: HashMap::new(),
+        }
+    }
+
+    pub async fn run(mut self) {
+        loop {
+            let next = self.probe_due().await;
+            sleep_until(next).await;
+        }
+    }
+
+    /// Starts the checks that are due and aren't still running, and returns when the
+    /// next round should start.
+    async fn probe_due(&mut self) -> Instant {
+        let now = Instant::now();
+        let checks = match self.checker.db.run(project_statuses::find_checks).await {
+            Ok(checks) => checks,
+            Err(e) => {
+                error!("Unable to load checks: {}", e);
+                return now + MAX_SLEEP;
+            }
+        };
+
+        self.running.retain(|_, check| !check.is_finished());
+        self.last_checked
+            .retain(|id, _| checks.iter().any(|(p, _)| &p.project_status_id == id));
+
+        let mut next = now + MAX_SLEEP;
+        for (project_status, project) in checks {
+            let interval = project_status
+                .check_interval_secs
+                .map_or(self.interval, |secs| Duration::from_secs(secs as u64));
+            let id = project_status.project_status_id.clone();
+            let due_at = self
+                .last_checked
+                .get(&id)
+                .map_or(now, |last| *last + interval);
+            if due_at > now || self.running.contains_key(&id) {
+                next = next.min(due_at);
+                continue;
+            }
+
+            self.last_checked.insert(id.clone(), now);
+            let checker = self.checker.clone();
+            let check = tokio::spawn(async move { checker.run(project_status, project).await });
+            self.running.insert(id, check);
+            next = next.min(now + interval);
+        }
+        next.max(now + MIN_SLEEP)
+    }
+}
+
+impl Checker {
+    /// Runs the check and saves its outcome. Only the health of the status is
+    /// written, the check definition read when the check started may be stale by now.
+    async fn run(&self, project_status: ProjectStatus, project: Project) {
+        let check_timeout = project_status
+            .check_timeout_ms
+            .map_or(self.timeout, |ms| Duration::from_millis(ms as u64));
+        let target = project_status
+            .check_target
+            .as_deref()
+            .unwrap_or(&project.url);
+        let (health_state, measurement) =
+            check(&self.client, &project_status, target, check_timeout).await;
+
+        let reporter = Reporter::new(ReportSource::Prober, None);
+        if let Err(e) = self
+            .reports
+            .save(
+                &self.db,
+                &project_status.project_status_id,
+                None,
+                Some(Report::Health(health_state)),
+                reporter,
+                measurement,
+            )
+            .await
+        {
+            error!(
+                "Unable to update '{}': {}",
+                project_status.project_status_id, e
+            );
+        }
+    }
+}
+
+/// Runs the check of `project_status` against `target`, giving up after
+/// `check_timeout`.
+async fn check(
+    client: &reqwest::Client,
+    project_status: &ProjectStatus,
+    target: &str,
+    check_timeout: Duration,
+) -> (HealthState, Measurement) {
+    let started = Instant::now();
+    let result = timeout(check_timeout, async {
+        match project_status.check_kind {
+            CheckKind::Http | CheckKind::Keyword => {
+                check_http(client, project_status, target).await
+            }
+            CheckKind::Tcp => TcpStream::connect(target).await.is_ok().into(),
+            CheckKind::GrpcHealth => check_grpc_health(project_status, target).await.into(),
+        }
+    })
+    .await;
+    match result {
+        Ok(health_state) => {
+            let latency_ms = i32::try_from(started.elapsed().as_millis()).ok();
+            (
+                health_state,
+                Measurement {
+                    latency_ms,
+                    detail: None,
+                },
+            )
+        }
+        Err(_) => (
+            HealthState::MajorOutage,
+            Measurement {
+                latency_ms: None,
+                detail: Some(format!("Timed out after {} ms", check_timeout.as_millis())),
+            },
+        ),
+    }
+}
+
+/// Checks the response against the expected status code and body. A response that
+/// only misses the latency expectation counts as degraded.
+async fn check_http(
+    client: &reqwest::Client,
+    project_status: &ProjectStatus,
+    target: &str,
+) -> HealthState {
+    let started = Instant::now();
+    let response = match client.get(target).send().await {
+        Ok(response) => response,
+        Err(_) => return HealthState::MajorOutage,
+    };
+    let latency = started.elapsed();
+
+    let status_ok = match project_status.check_expected_status {
+        Some(expected_status) => i32::from(response.status().as_u16()) == expected_status,
+        None => response.status().is_success(),
+    };
+    if !status_ok {
+        return HealthState::MajorOutage;
+    }
+
+    let body_ok = match &project_status.check_expected_body {
+        Some(expected_body) => match response.text().await {
+            Ok(body) => body.contains(expected_body.as_str()),
+            Err(_) => false,
+        },
+        None => true,
+    };
+    if !body_ok {
+        return HealthState::MajorOutage;
+    }
+
+    match project_status.check_max_latency_ms {
+        Some(max_latency_ms) if latency > Duration::from_millis(max_latency_ms as u64) => {
+            HealthState::Degraded
+        }
+        _ => HealthState::Operational,
+    }
+}
+
+async fn check_grpc_health(project_status: &ProjectStatus, target: &str) -> bool {
+    let mut client = match HealthClient::connect(target.to_string()).await {
+        Ok(client) => client,
+        Err(_) => return false,
+    };
+    let request = HealthCheckRequest {
+        service: project_status
+            .check_grpc_service
+            .clone()
+            .unwrap_or_default(),
+    };
+    match client.check(request).await {
+        Ok(response) => response.into_inner().status == ServingStatus::Serving as i32,
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::serde::json::{json, serde_json};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_millis(500);
+
+    /// Answers every connection on a local port with `response`, or never answers when
+    /// it's `None`, and returns the URL to check.
+    async fn serve(response: Option<&'static str>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut silent = Vec::new();
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = [0; 1024];
+                let _ = socket.read(&mut request).await;
+                match response {
+                    Some(response) => {
+                        let _ = socket.write_all(response.as_bytes()).await;
+                    }
+                    None => silent.push(socket),
+                }
+            }
+        });
+        format!("http://{}/", addr)
+    }
+
+    fn http_check() -> ProjectStatus {
+        serde_json::from_value(json!({
+            "project_status_id": "status",
+            "name": "api",
+            "is_healthy": true,
+            "project_id": "project",
+            "check_enabled": true,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn successful_response_is_operational() {
+        let target = serve(Some("HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")).await;
+        let (health_state, measurement) =
+            check(&reqwest::Client::new(), &http_check(), &target, TIMEOUT).await;
+        assert_eq!(health_state, HealthState::Operational);
+        assert!(measurement.latency_ms.is_some());
+        assert_eq!(measurement.detail, None);
+    }
+
+    #[tokio::test]
+    async fn error_response_is_an_outage() {
+        let target = serve(Some(
+            "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n",
+        ))
+        .await;
+        let (health_state, _) =
+            check(&reqwest::Client::new(), &http_check(), &target, TIMEOUT).await;
+        assert_eq!(health_state, HealthState::MajorOutage);
+    }
+
+    #[tokio::test]
+    async fn refused_connection_is_an_outage() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let target = format!("http://{}/", listener.local_addr().unwrap());
+        drop(listener);
+        let (health_state, _) =
+            check(&reqwest::Client::new(), &http_check(), &target, TIMEOUT).await;
+        assert_eq!(health_state, HealthState::MajorOutage);
+    }
+
+    #[tokio::test]
+    async fn unanswered_request_times_out() {
+        let target = serve(None).await;
+        let (health_state, measurement) =
+            check(&reqwest::Client::new(), &http_check(), &target, TIMEOUT).await;
+        assert_eq!(health_state, HealthState::MajorOutage);
+        assert_eq!(measurement.latency_ms, None);
+        assert_eq!(
+            measurement.detail.as_deref(),
+            Some("Timed out after 500 ms")
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use diesel::PgConnection;
+use rocket_sync_db_pools::ConnectionPool;
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::db::{projects, Db};
+
+/// How long a project's visibility is trusted before it's looked up again, which is
+/// also how long a visibility change takes to reach open event streams.
+const TTL: Duration = Duration::from_secs(10);
+
+/// Most projects kept at once. Past it, lookups go uncached until entries expire.
+const MAX_ENTRIES: usize = 10_000;
+
+/// Which projects are published, shared by every event stream so an event costs at
+/// most one lookup per project every `TTL` rather than one per subscriber.
+pub struct PublishedProjects {
+    pool: ConnectionPool<Db, PgConnection>,
+    cache: Mutex<HashMap<String, (bool, Instant)>>,
+}
+
+impl PublishedProjects {
+    pub fn new(pool: ConnectionPool<Db, PgConnection>) -> Self {
+        Self {
+            pool,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether the project `project_id` is published. Unknown projects and failed
+    /// lookups count as unpublished, and neither is cached, so made-up ids from events
+    /// can't grow the cache.
+    pub async fn contains(&self, project_id: &str) -> bool {
+        if let Some((published, fetched_at)) = self.cache.lock().unwrap().get(project_id) {
+            if fetched_at.elapsed() < TTL {
+                return *published;
+            }
+        }
+
+        let conn = match self.pool.get().await {
+            Some(conn) => conn,
+            None => return false,
+        };
+        let pid = project_id.to_string();
+        let published = match conn.run(move |conn| projects::find_by_id(conn, &pid)).await {
+            Ok(Some(project)) => project.visibility.is_listed(),
+            Ok(None) | Err(_) => return false,
+        };
+
+        let mut cache = self.cache.lock().unwrap();
+        cache.retain(|_, (_, fetched_at)| fetched_at.elapsed() < TTL);
+        if cache.len() < MAX_ENTRIES {
+            cache.insert(project_id.to_string(), (published, Instant::now()));
+        }
+        published
+    }
+}
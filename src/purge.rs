@@ -0,0 +1,90 @@
+use chrono::Utc;
+use std::{sync::Arc, time::Duration};
+use tokio::time::interval;
+
+use crate::{
+    db::{project_status_metrics, project_statuses, projects, Db},
+    errors::DbError,
+    media::{self, Storage},
+};
+
+const TICK: Duration = Duration::from_secs(60 * 60);
+
+/// Permanently deletes the projects and statuses that have been in the trash for
+/// longer than the trash retention period, along with the files of the projects'
+/// media, and the metric samples older than the metrics retention period.
+pub struct Purger {
+    db: Db,
+    storage: Arc<dyn Storage>,
+    trash_retention: Option<Duration>,
+    metrics_retention: Duration,
+}
+
+impl Purger {
+    pub fn new(
+        db: Db,
+        storage: Arc<dyn Storage>,
+        trash_retention: Option<Duration>,
+        metrics_retention: Duration,
+    ) -> Self {
+        Self {
+            db,
+            storage,
+            trash_retention,
+            metrics_retention,
+        }
+    }
+
+    pub async fn run(self) {
+        let mut ticker = interval(TICK);
+        loop {
+            ticker.tick().await;
+            self.purge_metrics().await;
+            if let Some(trash_retention) = self.trash_retention {
+                self.purge_trash(trash_retention).await;
+            }
+        }
+    }
+
+    async fn purge_metrics(&self) {
+        let retention = match chrono::Duration::from_std(self.metrics_retention) {
+            Ok(retention) => retention,
+            Err(_) => return,
+        };
+        let before = Utc::now() - retention;
+        if let Err(e) = self
+            .db
+            .run(move |conn| project_status_metrics::purge(conn, before))
+            .await
+        {
+            error!("Unable to purge metrics: {}", e);
+        }
+    }
+
+    async fn purge_trash(&self, trash_retention: Duration) {
+        let retention = match chrono::Duration::from_std(trash_retention) {
+            Ok(retention) => retention,
+            Err(_) => return,
+        };
+        let before = Utc::now() - retention;
+        let purged = self
+            .db
+            .run(move |conn| {
+                project_statuses::purge(conn, before)?;
+                let media = projects::purge(conn, before)?;
+                Ok::<_, DbError<'static>>(media)
+            })
+            .await;
+        let purged_media = match purged {
+            Ok(purged_media) => purged_media,
+            Err(e) => {
+                error!("Unable to purge the trash: {}", e);
+                return;
+            }
+        };
+
+        for project_media in purged_media {
+            media::remove(self.storage.as_ref(), &project_media).await;
+        }
+    }
+}
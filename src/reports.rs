@@ -0,0 +1,192 @@
+use chrono::{DateTime, Utc};
+use rocket::tokio::sync::broadcast::Sender;
+use std::borrow::Cow;
+use uuid::Uuid;
+
+use crate::{
+    db::{incidents, maintenance_windows, project_statuses, Db},
+    errors::DbError,
+    models::{
+        health_state::HealthState,
+        incident::IncidentDetail,
+        project_event::{ProjectEvent, ProjectStatusEvent},
+        project_status::ProjectStatus,
+        project_status_history::{ProjectStatusHistory, ReportSource},
+        project_status_metric::ProjectStatusMetric,
+    },
+    notifier::Notifier,
+};
+
+/// Who submitted a health report.
+#[derive(Clone, Debug)]
+pub struct Reporter {
+    pub source: ReportSource,
+    pub sub: Option<Uuid>,
+}
+
+impl Reporter {
+    pub fn new(source: ReportSource, sub: Option<Uuid>) -> Self {
+        Self { source, sub }
+    }
+}
+
+/// Optional measurements sent along with a health report.
+#[derive(Clone, Debug, Default)]
+pub struct Measurement {
+    pub latency_ms: Option<i32>,
+    pub detail: Option<String>,
+}
+
+/// What a report says about a status' health.
+#[derive(Clone, Copy, Debug)]
+pub enum Report {
+    /// A health state that is applied subject to the status' thresholds.
+    Health(HealthState),
+    /// A health state that is applied outright, as when maintenance starts or ends.
+    Override(HealthState),
+}
+
+/// Persists health reports coming from REST, gRPC and the prober alike.
+#[derive(Clone)]
+pub struct Reports {
+    notifier: Notifier,
+    events: Sender<ProjectEvent>,
+}
+
+impl Reports {
+    pub fn new(notifier: Notifier, events: Sender<ProjectEvent>) -> Self {
+        Self { notifier, events }
+    }
+
+    /// Applies `report` to the status `id` and persists it along with `changes` to the
+    /// status' settings if given. The report is applied to the status row as locked in
+    /// the database, so concurrent reports can't undo each other. Records the
+    /// transition in the status history if its health state changed and the
+    /// measurement if there is one, then notifies the project's recipients, opens or
+    /// resolves automatic incidents and publishes the status on the event stream if
+    /// it changed. A status inside an active maintenance window is kept in the
+    /// maintenance state whatever was reported.
+    pub async fn save<'a>(
+        &self,
+        db: &Db,
+        id: &str,
+        changes: Option<ProjectStatus>,
+        report: Option<Report>,
+        mut reporter: Reporter,
+        measurement: Measurement,
+    ) -> Result<ProjectStatus, DbError<'a>> {
+        let now = Utc::now();
+        let metric =
+            (measurement.latency_ms.is_some() || measurement.detail.is_some()).then(|| {
+                ProjectStatusMetric {
+                    project_status_metric_id: Uuid::new_v4().to_string(),
+                    project_status_id: id.to_string(),
+                    latency_ms: measurement.latency_ms,
+                    detail: measurement.detail.clone(),
+                    created_at: now,
+                }
+            });
+
+        let id_update = Cow::Owned(id.to_string());
+        let (previous, updated) = db
+            .run(move |conn| {
+                project_statuses::update_with_report(
+                    conn,
+                    &id_update,
+                    |conn, previous| {
+                        let mut updated = changes.unwrap_or_else(|| previous.clone());
+                        updated.take_health_of(previous);
+                        updated.updated_at = previous.updated_at;
+                        match report {
+                            Some(Report::Health(health_state)) => {
+                                updated.record_report(health_state, now)
+                            }
+                            Some(Report::Override(health_state)) => {
+                                updated.set_health_state(health_state)
+                            }
+                            None => {}
+                        }
+                        if maintenance_windows::is_active(conn, &previous.project_status_id, now)? {
+                            updated.set_health_state(HealthState::Maintenance);
+                            if previous.health_state != HealthState::Maintenance {
+                                reporter = Reporter::new(ReportSource::Maintenance, None);
+                            }
+                        }
+
+                        let history = (previous.health_state != updated.health_state).then(|| {
+                            ProjectStatusHistory {
+                                project_status_history_id: Uuid::new_v4().to_string(),
+                                project_status_id: previous.project_status_id.clone(),
+                                old_state: previous.health_state,
+                                new_state: updated.health_state,
+                                source: reporter.source,
+                                reporter: reporter.sub.map(|sub| sub.to_string()),
+                                created_at: now,
+                            }
+                        });
+                        Ok((updated, history))
+                    },
+                    metric,
+                )
+            })
+            .await?;
+        let previous = &previous;
+
+        self.notifier
+            .notify_transition(db, previous, &updated)
+            .await;
+
+        // `updated_at` only moves when something else did.
+        if previous != &updated {
+            self.publish(ProjectEvent::ProjectStatus(ProjectStatusEvent {
+                project_status: updated.clone(),
+                latency_ms: measurement.latency_ms,
+                detail: measurement.detail,
+            }));
+        }
+
+        self.track_incidents(db, previous, &updated, now).await;
+
+        Ok(updated)
+    }
+
+    pub fn publish(&self, event: ProjectEvent) {
+        // Nobody listening on the stream is not an error.
+        let _ = self.events.send(event);
+    }
+
+    /// Opens an incident when a status goes into an outage and resolves the automatic
+    /// ones once it recovers. Failures are logged, the report itself has been saved.
+    async fn track_incidents(
+        &self,
+        db: &Db,
+        previous: &ProjectStatus,
+        updated: &ProjectStatus,
+        now: DateTime<Utc>,
+    ) {
+        let result = if updated.health_state.is_outage() && !previous.health_state.is_outage() {
+            let project_status = updated.clone();
+            db.run(move |conn| incidents::open_automatic(conn, &project_status, now))
+                .await
+                .map(|incident| incident.into_iter().collect::<Vec<IncidentDetail>>())
+        } else if updated.is_healthy && !previous.is_healthy {
+            let psid = updated.project_status_id.clone();
+            db.run(move |conn| incidents::resolve_automatic(conn, &psid, now))
+                .await
+        } else {
+            return;
+        };
+
+        match result {
+            Ok(details) => {
+                for incident in details {
+                    self.publish(ProjectEvent::Incident(incident));
+                }
+            }
+            Err(e) => error!(
+                "Tracking incidents for {} failed: {}",
+                updated.project_status_id, e
+            ),
+        }
+    }
+}
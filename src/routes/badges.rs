@@ -0,0 +1,84 @@
+use std::borrow::Cow;
+
+use crate::{
+    badge::{Badge, BadgeStyle},
+    db::{project_statuses, projects, uptime, Db},
+    errors::{AppError, CustomError, DbError},
+    models::rollup::ProjectHealth,
+};
+
+#[get("/<name>/status.svg?<style>")]
+pub async fn get_status_badge<'a>(
+    db: Db,
+    name: &str,
+    style: Option<BadgeStyle>,
+) -> Result<Badge, AppError<'a>> {
+    let name_find = Cow::Owned(name.to_string());
+    let health = db
+        .run(move |conn| {
+            let project = match projects::find_by_name(conn, &name_find)? {
+                Some(project) if project.visibility.is_public() => project,
+                _ => return Ok(None),
+            };
+            let statuses = project_statuses::find_by_project(conn, &project.project_id)?;
+            Ok::<_, DbError<'static>>(Some(project.rollup_policy.apply(&statuses)))
+        })
+        .await?;
+    let (message, color) = match health {
+        Some(ProjectHealth::Healthy) => ("operational", "#4c1"),
+        Some(ProjectHealth::Degraded) => ("degraded", "#dfb317"),
+        Some(ProjectHealth::Down) => ("down", "#e05d44"),
+        Some(ProjectHealth::Unknown) => ("unknown", "#9f9f9f"),
+        None => return Err(CustomError::RecordDoesNotExist(name).into()),
+    };
+    Ok(Badge::new(
+        "status",
+        message,
+        color,
+        style.unwrap_or_default(),
+    ))
+}
+
+/// `window` is one of `24h`, `7d`, `30d` (the default) or `90d`.
+#[get("/<name>/uptime.svg?<style>&<window>")]
+pub async fn get_uptime_badge<'a>(
+    db: Db,
+    name: &str,
+    style: Option<BadgeStyle>,
+    window: Option<&str>,
+) -> Result<Badge, AppError<'a>> {
+    let name_find = Cow::Owned(name.to_string());
+    let project_uptime = db
+        .run(move |conn| {
+            let project = match projects::find_by_name(conn, &name_find)? {
+                Some(project) if project.visibility.is_public() => project,
+                _ => return Ok(None),
+            };
+            uptime::find_by_project(conn, &project.project_id)
+        })
+        .await?;
+    let uptime = match project_uptime {
+        Some(project_uptime) => project_uptime.uptime,
+        None => return Err(CustomError::RecordDoesNotExist(name).into()),
+    };
+
+    let (label, percentage) = match window.unwrap_or("30d") {
+        "24h" => ("uptime 24h", uptime.last_24h),
+        "7d" => ("uptime 7d", uptime.last_7d),
+        "90d" => ("uptime 90d", uptime.last_90d),
+        _ => ("uptime 30d", uptime.last_30d),
+    };
+    let (message, color) = match percentage {
+        Some(p) if p >= 99.9 => (format!("{:.2}%", p), "#4c1"),
+        Some(p) if p >= 99.0 => (format!("{:.2}%", p), "#97ca00"),
+        Some(p) if p >= 95.0 => (format!("{:.2}%", p), "#dfb317"),
+        Some(p) => (format!("{:.2}%", p), "#e05d44"),
+        None => (String::from("no data"), "#9f9f9f"),
+    };
+    Ok(Badge::new(
+        label,
+        &message,
+        color,
+        style.unwrap_or_default(),
+    ))
+}
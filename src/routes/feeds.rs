@@ -0,0 +1,115 @@
+use rocket::{
+    http::{ContentType, RawStr},
+    State,
+};
+use std::borrow::Cow;
+
+use crate::{
+    config::AppConfig,
+    db::{feeds, projects, Db},
+    errors::{AppError, CustomError, DbError},
+    feed::{self, Feed},
+};
+
+#[get("/atom.xml")]
+pub async fn get_atom_feed<'a>(
+    db: Db,
+    config: &State<AppConfig>,
+) -> Result<(ContentType, String), AppError<'a>> {
+    let feed = find_feed(db, config, "atom.xml").await?;
+    Ok((atom_type(), feed.atom()))
+}
+
+#[get("/rss.xml")]
+pub async fn get_rss_feed<'a>(
+    db: Db,
+    config: &State<AppConfig>,
+) -> Result<(ContentType, String), AppError<'a>> {
+    let feed = find_feed(db, config, "rss.xml").await?;
+    Ok((rss_type(), feed.rss()))
+}
+
+#[get("/<name>/atom.xml")]
+pub async fn get_project_atom_feed<'a>(
+    db: Db,
+    config: &State<AppConfig>,
+    name: &str,
+) -> Result<(ContentType, String), AppError<'a>> {
+    match find_project_feed(db, config, name, "atom.xml").await? {
+        Some(feed) => Ok((atom_type(), feed.atom())),
+        None => Err(CustomError::RecordDoesNotExist(name).into()),
+    }
+}
+
+#[get("/<name>/rss.xml")]
+pub async fn get_project_rss_feed<'a>(
+    db: Db,
+    config: &State<AppConfig>,
+    name: &str,
+) -> Result<(ContentType, String), AppError<'a>> {
+    match find_project_feed(db, config, name, "rss.xml").await? {
+        Some(feed) => Ok((rss_type(), feed.rss())),
+        None => Err(CustomError::RecordDoesNotExist(name).into()),
+    }
+}
+
+/// The feed of every published project.
+async fn find_feed<'c>(
+    db: Db,
+    config: &'c State<AppConfig>,
+    file: &str,
+) -> Result<Feed<'c>, DbError<'static>> {
+    let entries = db.run(|conn| feeds::find_entries(conn, None)).await?;
+
+    let public_url = config.public_url.as_str();
+    Ok(Feed {
+        title: String::from("Status"),
+        link: feed::status_page_url(public_url, None),
+        self_link: format!("{}/feeds/{}", public_url, file),
+        public_url,
+        entries,
+    })
+}
+
+/// The feed of the project called `name`, which is `None` when there is no such
+/// project or it's a draft or archived.
+async fn find_project_feed<'c>(
+    db: Db,
+    config: &'c State<AppConfig>,
+    name: &str,
+    file: &str,
+) -> Result<Option<Feed<'c>>, DbError<'static>> {
+    let name_find = Cow::Owned(name.to_string());
+    let found = db
+        .run(move |conn| {
+            let project = match projects::find_by_name(conn, &name_find)? {
+                Some(project) if project.visibility.is_public() => project,
+                _ => return Ok(None),
+            };
+            let entries = feeds::find_entries(conn, Some(&project.project_id))?;
+            Ok::<_, DbError<'static>>(Some((project, entries)))
+        })
+        .await?;
+
+    let public_url = config.public_url.as_str();
+    Ok(found.map(|(project, entries)| Feed {
+        title: format!("{} status", project.name),
+        link: feed::status_page_url(public_url, Some(&project.name)),
+        self_link: format!(
+            "{}/feeds/{}/{}",
+            public_url,
+            RawStr::new(&project.name).percent_encode(),
+            file
+        ),
+        public_url,
+        entries,
+    }))
+}
+
+fn atom_type() -> ContentType {
+    ContentType::new("application", "atom+xml")
+}
+
+fn rss_type() -> ContentType {
+    ContentType::new("application", "rss+xml")
+}
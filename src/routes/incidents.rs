@@ -0,0 +1,165 @@
+use chrono::Utc;
+use rocket::{http::Status, serde::json::Json, State};
+use std::borrow::Cow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    db::{incidents, projects, Db},
+    errors::{AppError, CustomError, DbError},
+    models::{
+        incident::{
+            Incident, IncidentCreate, IncidentDetail, IncidentTimelineEntry, IncidentUpdate,
+        },
+        project_event::ProjectEvent,
+    },
+    reports::Reports,
+    request_guards::bearer_auth::BearerAuth,
+    routes::projects::find_visible_project,
+};
+
+/// Unauthenticated requests only get the incidents of published projects.
+#[get("/")]
+pub async fn get_incidents<'a>(
+    db: Db,
+    auth: Option<BearerAuth<'_>>,
+) -> Result<Json<Vec<Incident>>, AppError<'a>> {
+    let published_only = auth.is_none();
+    let incidents = db
+        .run(move |conn| incidents::find(conn, published_only))
+        .await?;
+    Ok(Json(incidents))
+}
+
+#[get("/<id>")]
+pub async fn get_incident<'a>(
+    db: Db,
+    auth: Option<BearerAuth<'_>>,
+    id: &str,
+) -> Result<Json<IncidentDetail>, AppError<'a>> {
+    let id_find = Cow::Owned(id.to_string());
+    let authenticated = auth.is_some();
+    let incident = db
+        .run(move |conn| {
+            let incident = match incidents::find_detail(conn, &id_find)? {
+                Some(incident) => incident,
+                None => return Ok(None),
+            };
+            let visible = projects::is_visible(conn, &incident.incident.project_id, authenticated)?;
+            Ok::<_, DbError<'static>>(visible.then_some(incident))
+        })
+        .await?;
+    match incident {
+        Some(incident) => Ok(Json(incident)),
+        None => Err(CustomError::RecordDoesNotExist(id).into()),
+    }
+}
+
+#[get("/project/<project_id>")]
+pub async fn get_incidents_by_project<'a>(
+    db: Db,
+    auth: Option<BearerAuth<'_>>,
+    project_id: &str,
+) -> Result<Json<Vec<Incident>>, AppError<'a>> {
+    find_visible_project(&db, auth.as_ref(), project_id).await?;
+
+    let project_id_find = Cow::Owned(project_id.to_string());
+    let incidents = db
+        .run(move |conn| incidents::find_by_project(conn, &project_id_find))
+        .await?;
+    Ok(Json(incidents))
+}
+
+#[post("/", data = "<incident>")]
+pub async fn create_incident<'a>(
+    db: Db,
+    reports: &State<Reports>,
+    incident: Json<IncidentCreate<'_>>,
+) -> Result<Json<IncidentDetail>, AppError<'a>> {
+    incident.validate()?;
+
+    let now = Utc::now();
+    let mut new_incident = Incident {
+        incident_id: Uuid::new_v4().to_string(),
+        project_id: incident.project_id.to_string(),
+        title: incident.title.to_string(),
+        severity: incident.severity,
+        state: Default::default(),
+        automatic: false,
+        created_at: now,
+        resolved_at: None,
+    };
+    new_incident.set_state(incident.state.unwrap_or_default(), now);
+    let entry = IncidentTimelineEntry::new(&new_incident, incident.message.to_string(), now);
+    let psids = incident.project_status_ids.clone();
+    let incident = db
+        .run(move |conn| incidents::create(conn, new_incident, entry, &psids))
+        .await?;
+
+    reports.publish(ProjectEvent::Incident(incident.clone()));
+    Ok(Json(incident))
+}
+
+#[put("/<id>", data = "<incident>")]
+pub async fn update_incident<'a>(
+    db: Db,
+    reports: &State<Reports>,
+    id: &str,
+    incident: Json<IncidentUpdate<'_>>,
+) -> Result<Json<IncidentDetail>, AppError<'a>> {
+    incident.validate()?;
+
+    let id_find = Cow::Owned(id.to_string());
+    let id_update = Cow::Owned(id.to_string());
+    let mut updated_incident = match db
+        .run(move |conn| incidents::find_by_id(conn, &id_find))
+        .await?
+    {
+        Some(incident) => incident,
+        None => return Err(CustomError::RecordDoesNotExist(id).into()),
+    };
+
+    let now = Utc::now();
+    if let Some(new_title) = incident.title {
+        updated_incident.title = new_title.to_string();
+    }
+    if let Some(new_severity) = incident.severity {
+        updated_incident.severity = new_severity;
+    }
+    if let Some(new_state) = incident.state {
+        updated_incident.set_state(new_state, now);
+    }
+    let entry = match (incident.message, incident.state) {
+        (Some(message), _) => Some(message.to_string()),
+        (None, Some(new_state)) => Some(format!("Marked as {}", new_state.label())),
+        (None, None) => None,
+    }
+    .map(|message| IncidentTimelineEntry::new(&updated_incident, message, now));
+    let psids = incident.project_status_ids.clone();
+    let incident = db
+        .run(move |conn| {
+            incidents::update(conn, &id_update, updated_incident, entry, psids.as_deref())
+        })
+        .await?;
+
+    reports.publish(ProjectEvent::Incident(incident.clone()));
+    Ok(Json(incident))
+}
+
+#[delete("/<id>")]
+pub async fn delete_incident<'a>(db: Db, id: &str) -> Result<Status, AppError<'a>> {
+    let id_find = Cow::Owned(id.to_string());
+    let id_delete = Cow::Owned(id.to_string());
+    if db
+        .run(move |conn| incidents::find_by_id(conn, &id_find))
+        .await?
+        .is_none()
+    {
+        return Err(CustomError::RecordDoesNotExist(id).into());
+    }
+
+    db.run(move |conn| incidents::delete(conn, &id_delete))
+        .await?;
+
+    Ok(Status::NoContent)
+}
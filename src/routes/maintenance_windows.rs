@@ -0,0 +1,176 @@
+use chrono::Utc;
+use rocket::{
+    http::{ContentType, Status},
+    serde::json::Json,
+};
+use std::borrow::Cow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    calendar,
+    db::{maintenance_windows, projects, Db},
+    errors::{AppError, CustomError, DbError},
+    models::maintenance_window::{
+        MaintenanceWindow, MaintenanceWindowCreate, MaintenanceWindowDetail,
+        MaintenanceWindowUpdate,
+    },
+    request_guards::bearer_auth::BearerAuth,
+    routes::projects::find_visible_project,
+};
+
+#[get("/<id>")]
+pub async fn get_maintenance_window<'a>(
+    db: Db,
+    auth: Option<BearerAuth<'_>>,
+    id: &str,
+) -> Result<Json<MaintenanceWindowDetail>, AppError<'a>> {
+    let id_find = Cow::Owned(id.to_string());
+    let authenticated = auth.is_some();
+    let maintenance_window = db
+        .run(move |conn| {
+            let maintenance_window = match maintenance_windows::find_detail(conn, &id_find)? {
+                Some(maintenance_window) => maintenance_window,
+                None => return Ok(None),
+            };
+            let pid = &maintenance_window.maintenance_window.project_id;
+            let visible = projects::is_visible(conn, pid, authenticated)?;
+            Ok::<_, DbError<'static>>(visible.then_some(maintenance_window))
+        })
+        .await?;
+    match maintenance_window {
+        Some(maintenance_window) => Ok(Json(maintenance_window)),
+        None => Err(CustomError::RecordDoesNotExist(id).into()),
+    }
+}
+
+#[get("/project/<project_id>")]
+pub async fn get_maintenance_windows_by_project<'a>(
+    db: Db,
+    auth: Option<BearerAuth<'_>>,
+    project_id: &str,
+) -> Result<Json<Vec<MaintenanceWindow>>, AppError<'a>> {
+    find_visible_project(&db, auth.as_ref(), project_id).await?;
+
+    let project_id_find = Cow::Owned(project_id.to_string());
+    let maintenance_windows = db
+        .run(move |conn| maintenance_windows::find_by_project(conn, &project_id_find))
+        .await?;
+    Ok(Json(maintenance_windows))
+}
+
+#[get("/project/<project_id>/calendar.ics")]
+pub async fn get_maintenance_calendar<'a>(
+    db: Db,
+    auth: Option<BearerAuth<'_>>,
+    project_id: &str,
+) -> Result<(ContentType, String), AppError<'a>> {
+    let project_id_find = Cow::Owned(project_id.to_string());
+    let now = Utc::now();
+    let (project, upcoming) = db
+        .run(move |conn| {
+            let project = projects::find_by_id(conn, &project_id_find)?;
+            let upcoming =
+                maintenance_windows::find_upcoming_by_project(conn, &project_id_find, now)?;
+            Ok::<_, DbError<'static>>((project, upcoming))
+        })
+        .await?;
+    match project {
+        Some(project) if auth.is_some() || project.visibility.is_public() => Ok((
+            ContentType::Calendar,
+            calendar::maintenance_calendar(&project, &upcoming, now),
+        )),
+        _ => Err(CustomError::RecordDoesNotExist(project_id).into()),
+    }
+}
+
+#[post("/", data = "<maintenance_window>")]
+pub async fn create_maintenance_window<'a>(
+    db: Db,
+    maintenance_window: Json<MaintenanceWindowCreate<'_>>,
+) -> Result<Json<MaintenanceWindowDetail>, AppError<'a>> {
+    maintenance_window.validate()?;
+
+    let new_maintenance_window = MaintenanceWindow {
+        maintenance_window_id: Uuid::new_v4().to_string(),
+        project_id: maintenance_window.project_id.to_string(),
+        message: maintenance_window.message.to_string(),
+        starts_at: maintenance_window.starts_at,
+        ends_at: maintenance_window.ends_at,
+        created_at: Utc::now(),
+    };
+    new_maintenance_window.validate_period()?;
+
+    let psids = maintenance_window.project_status_ids.clone();
+    let maintenance_window = db
+        .run(move |conn| maintenance_windows::create(conn, new_maintenance_window, &psids))
+        .await?;
+    Ok(Json(maintenance_window))
+}
+
+#[put("/<id>", data = "<maintenance_window>")]
+pub async fn update_maintenance_window<'a>(
+    db: Db,
+    id: &str,
+    maintenance_window: Json<MaintenanceWindowUpdate<'_>>,
+) -> Result<Json<MaintenanceWindowDetail>, AppError<'a>> {
+    maintenance_window.validate()?;
+
+    let id_find = Cow::Owned(id.to_string());
+    let id_update = Cow::Owned(id.to_string());
+    let existing_maintenance_window = match db
+        .run(move |conn| maintenance_windows::find_by_id(conn, &id_find))
+        .await?
+    {
+        Some(maintenance_window) => maintenance_window,
+        None => return Err(CustomError::RecordDoesNotExist(id).into()),
+    };
+
+    let updated_maintenance_window = MaintenanceWindow {
+        maintenance_window_id: existing_maintenance_window.maintenance_window_id,
+        project_id: existing_maintenance_window.project_id,
+        message: match maintenance_window.message {
+            Some(new_message) => new_message.to_string(),
+            None => existing_maintenance_window.message,
+        },
+        starts_at: maintenance_window
+            .starts_at
+            .unwrap_or(existing_maintenance_window.starts_at),
+        ends_at: maintenance_window
+            .ends_at
+            .unwrap_or(existing_maintenance_window.ends_at),
+        created_at: existing_maintenance_window.created_at,
+    };
+    updated_maintenance_window.validate_period()?;
+
+    let psids = maintenance_window.project_status_ids.clone();
+    let maintenance_window = db
+        .run(move |conn| {
+            maintenance_windows::update(
+                conn,
+                &id_update,
+                updated_maintenance_window,
+                psids.as_deref(),
+            )
+        })
+        .await?;
+    Ok(Json(maintenance_window))
+}
+
+#[delete("/<id>")]
+pub async fn delete_maintenance_window<'a>(db: Db, id: &str) -> Result<Status, AppError<'a>> {
+    let id_find = Cow::Owned(id.to_string());
+    let id_delete = Cow::Owned(id.to_string());
+    if db
+        .run(move |conn| maintenance_windows::find_by_id(conn, &id_find))
+        .await?
+        .is_none()
+    {
+        return Err(CustomError::RecordDoesNotExist(id).into());
+    }
+
+    db.run(move |conn| maintenance_windows::delete(conn, &id_delete))
+        .await?;
+
+    Ok(Status::NoContent)
+}
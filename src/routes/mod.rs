@@ -1,4 +1,14 @@
+pub mod badges;
+pub mod feeds;
 pub mod health;
+pub mod incidents;
+pub mod maintenance_windows;
 pub mod project_events;
+pub mod project_links;
+pub mod project_media;
+pub mod project_recipients;
 pub mod project_statuses;
 pub mod projects;
+pub mod status_page;
+pub mod tags;
+pub mod trash;
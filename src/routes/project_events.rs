@@ -4,14 +4,21 @@ use rocket::tokio::select;
 use rocket::tokio::sync::broadcast::{error::RecvError, Sender};
 use rocket::{Shutdown, State};
 
-use crate::models::project_status::ProjectStatus;
+use crate::{
+    models::project_event::ProjectEvent, published::PublishedProjects,
+    request_guards::bearer_auth::BearerAuth,
+};
 
+/// Unauthenticated subscribers only get the events of published projects.
 #[get("/")]
-pub async fn project_status_events(
-    queue: &State<Sender<ProjectStatus>>,
+pub async fn project_status_events<'a>(
+    queue: &State<Sender<ProjectEvent>>,
+    published: &'a State<PublishedProjects>,
+    auth: Option<BearerAuth<'_>>,
     mut end: Shutdown,
-) -> EventStream![] {
+) -> EventStream![Event + 'a] {
     let mut rx = queue.subscribe();
+    let published_only = auth.is_none();
     EventStream! {
         loop {
             let msg = select! {
@@ -22,17 +29,24 @@ pub async fn project_status_events(
                 },
                 _ = &mut end => break
             };
+            if published_only && !published.contains(msg.project_id()).await {
+                continue;
+            }
 
-            yield Event::json(&msg);
+            let event = Event::json(&msg);
+            yield match msg.name() {
+                Some(name) => event.event(name),
+                None => event,
+            };
         }
     }
 }
 
-#[post("/", data = "<project_status>")]
+#[post("/", data = "<project_event>")]
 pub fn publish_project_status_event(
-    queue: &State<Sender<ProjectStatus>>,
-    project_status: Json<ProjectStatus>,
+    queue: &State<Sender<ProjectEvent>>,
+    project_event: Json<ProjectEvent>,
 ) -> Result<(), ()> {
-    queue.send(project_status.into_inner()).map_err(|_| ())?;
+    queue.send(project_event.into_inner()).map_err(|_| ())?;
     Ok(())
 }
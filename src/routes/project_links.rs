@@ -0,0 +1,121 @@
+use chrono::Utc;
+use rocket::{http::Status, serde::json::Json};
+use std::borrow::Cow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    db::{project_links, projects, Db},
+    errors::{AppError, CustomError},
+    models::project_link::{ProjectLink, ProjectLinkCreate, ProjectLinkUpdate},
+    request_guards::bearer_auth::BearerAuth,
+    routes::projects::find_visible_project,
+};
+
+#[get("/<id>/links", rank = 2)]
+pub async fn get_project_links<'a>(
+    db: Db,
+    auth: Option<BearerAuth<'_>>,
+    id: &str,
+) -> Result<Json<Vec<ProjectLink>>, AppError<'a>> {
+    find_visible_project(&db, auth.as_ref(), id).await?;
+
+    let id_links = Cow::Owned(id.to_string());
+
+    let links = db
+        .run(move |conn| project_links::find_by_project(conn, &id_links))
+        .await?;
+    Ok(Json(links))
+}
+
+#[post("/<id>/links", data = "<link>", rank = 2)]
+pub async fn create_project_link<'a>(
+    db: Db,
+    id: &str,
+    link: Json<ProjectLinkCreate<'_>>,
+) -> Result<Json<ProjectLink>, AppError<'a>> {
+    link.validate()?;
+
+    let id_find = Cow::Owned(id.to_string());
+    if db
+        .run(move |conn| projects::find_by_id(conn, &id_find))
+        .await?
+        .is_none()
+    {
+        return Err(CustomError::RecordDoesNotExist(id).into());
+    }
+
+    let new_link = ProjectLink {
+        project_link_id: Uuid::new_v4().to_string(),
+        project_id: id.to_string(),
+        kind: link.kind.unwrap_or_default(),
+        url: link.url.to_string(),
+        label: link.label.map(|l| l.to_string()),
+        created_at: Utc::now(),
+    };
+    let link = db
+        .run(move |conn| project_links::create(conn, new_link))
+        .await?;
+    Ok(Json(link))
+}
+
+#[put("/<id>/links/<link_id>", data = "<link>", rank = 2)]
+pub async fn update_project_link<'a>(
+    db: Db,
+    id: &str,
+    link_id: &str,
+    link: Json<ProjectLinkUpdate<'_>>,
+) -> Result<Json<ProjectLink>, AppError<'a>> {
+    link.validate()?;
+
+    let link_find = Cow::Owned(link_id.to_string());
+    let link_update = Cow::Owned(link_id.to_string());
+    let existing_link = match db
+        .run(move |conn| project_links::find_by_id(conn, &link_find))
+        .await?
+    {
+        Some(existing_link) if existing_link.project_id == id => existing_link,
+        _ => return Err(CustomError::RecordDoesNotExist(link_id).into()),
+    };
+
+    let updated_link = ProjectLink {
+        project_link_id: existing_link.project_link_id,
+        project_id: existing_link.project_id,
+        kind: link.kind.unwrap_or(existing_link.kind),
+        url: match link.url {
+            Some(new_url) => new_url.to_string(),
+            None => existing_link.url,
+        },
+        label: match link.label {
+            Some(new_label) => Some(new_label.to_string()),
+            None => existing_link.label,
+        },
+        created_at: existing_link.created_at,
+    };
+    let updated_link = db
+        .run(move |conn| project_links::update(conn, &link_update, updated_link))
+        .await?;
+    Ok(Json(updated_link))
+}
+
+#[delete("/<id>/links/<link_id>", rank = 2)]
+pub async fn delete_project_link<'a>(
+    db: Db,
+    id: &str,
+    link_id: &str,
+) -> Result<Status, AppError<'a>> {
+    let link_find = Cow::Owned(link_id.to_string());
+    let link_delete = Cow::Owned(link_id.to_string());
+    match db
+        .run(move |conn| project_links::find_by_id(conn, &link_find))
+        .await?
+    {
+        Some(existing_link) if existing_link.project_id == id => {}
+        _ => return Err(CustomError::RecordDoesNotExist(link_id).into()),
+    }
+
+    db.run(move |conn| project_links::delete(conn, &link_delete))
+        .await?;
+
+    Ok(Status::NoContent)
+}
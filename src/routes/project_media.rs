@@ -0,0 +1,152 @@
+use chrono::Utc;
+use rocket::{form::Form, fs::TempFile, http::Status, serde::json::Json, tokio::fs, State};
+use std::{borrow::Cow, env, io, sync::Arc};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    config::AppConfig,
+    db::{project_media, projects, Db},
+    errors::{AppError, CustomError},
+    media::{self, Storage},
+    models::project_media::{ProjectMedia, ProjectMediaUpload},
+    request_guards::bearer_auth::BearerAuth,
+    routes::projects::find_visible_project,
+};
+
+#[get("/<id>/media", rank = 2)]
+pub async fn get_project_media<'a>(
+    db: Db,
+    auth: Option<BearerAuth<'_>>,
+    id: &str,
+) -> Result<Json<Vec<ProjectMedia>>, AppError<'a>> {
+    find_visible_project(&db, auth.as_ref(), id).await?;
+
+    let id_media = Cow::Owned(id.to_string());
+
+    let media = db
+        .run(move |conn| project_media::find_by_project(conn, &id_media))
+        .await?;
+    Ok(Json(media))
+}
+
+/// Takes a `multipart/form-data` image. Uploading a cover replaces the previous one.
+#[post("/<id>/media", data = "<upload>", rank = 2)]
+pub async fn upload_project_media<'a>(
+    db: Db,
+    config: &State<AppConfig>,
+    storage: &State<Arc<dyn Storage>>,
+    id: &str,
+    mut upload: Form<ProjectMediaUpload<'_>>,
+) -> Result<Json<ProjectMedia>, AppError<'a>> {
+    upload.validate()?;
+
+    let id_find = Cow::Owned(id.to_string());
+    if db
+        .run(move |conn| projects::find_by_id(conn, &id_find))
+        .await?
+        .is_none()
+    {
+        return Err(CustomError::RecordDoesNotExist(id).into());
+    }
+
+    let max_bytes = config.media.max_bytes;
+    if !upload.file.is_complete() || upload.file.len() > max_bytes {
+        return Err(CustomError::MediaTooLarge(max_bytes).into());
+    }
+    let bytes = read_upload(&mut upload.file).await.map_err(|e| {
+        error!("Failed to read uploaded media: {}", e);
+        AppError::default()
+    })?;
+
+    let (image, bytes) = rocket::tokio::task::spawn_blocking(move || {
+        let image = media::process(&bytes);
+        (image, bytes)
+    })
+    .await
+    .map_err(|_| AppError::default())?;
+    let file_name = upload.file.name().unwrap_or("file");
+    let image = match image {
+        Ok(image) => image,
+        Err(_) => return Err(CustomError::UnsupportedMedia(file_name).into()),
+    };
+
+    let project_media_id = Uuid::new_v4().to_string();
+    let storage_key = format!("projects/{}/{}.{}", id, project_media_id, image.extension);
+    let thumbnail_key = format!("projects/{}/{}-thumb.png", id, project_media_id);
+    let byte_size = bytes.len() as i32;
+    let url = store(storage, &storage_key, image.content_type, bytes).await?;
+    let thumbnail_url = store(storage, &thumbnail_key, "image/png", image.thumbnail).await?;
+
+    let new_media = ProjectMedia {
+        project_media_id,
+        project_id: id.to_string(),
+        kind: upload.kind.unwrap_or_default(),
+        content_type: image.content_type.to_string(),
+        byte_size,
+        width: image.width as i32,
+        height: image.height as i32,
+        caption: upload.caption.clone(),
+        url,
+        thumbnail_url,
+        storage_key,
+        thumbnail_key,
+        created_at: Utc::now(),
+    };
+    let (media, replaced) = db
+        .run(move |conn| project_media::create(conn, new_media))
+        .await?;
+    for replaced in replaced {
+        media::remove(storage.inner().as_ref(), &replaced).await;
+    }
+    Ok(Json(media))
+}
+
+#[delete("/<id>/media/<media_id>", rank = 2)]
+pub async fn delete_project_media<'a>(
+    db: Db,
+    storage: &State<Arc<dyn Storage>>,
+    id: &str,
+    media_id: &str,
+) -> Result<Status, AppError<'a>> {
+    let media_find = Cow::Owned(media_id.to_string());
+    let media_delete = Cow::Owned(media_id.to_string());
+    let media = match db
+        .run(move |conn| project_media::find_by_id(conn, &media_find))
+        .await?
+    {
+        Some(media) if media.project_id == id => media,
+        _ => return Err(CustomError::RecordDoesNotExist(media_id).into()),
+    };
+
+    db.run(move |conn| project_media::delete(conn, &media_delete))
+        .await?;
+    media::remove(storage.inner().as_ref(), &media).await;
+
+    Ok(Status::NoContent)
+}
+
+/// The uploaded bytes. Uploads buffered in memory are copied out through a temporary
+/// file, the only way to reach them.
+async fn read_upload(file: &mut TempFile<'_>) -> io::Result<Vec<u8>> {
+    if let Some(path) = file.path() {
+        return fs::read(path).await;
+    }
+    let path = env::temp_dir().join(format!("upload-{}", Uuid::new_v4()));
+    file.copy_to(&path).await?;
+    let bytes = fs::read(&path).await;
+    let _ = fs::remove_file(&path).await;
+    bytes
+}
+
+async fn store<'a>(
+    storage: &State<Arc<dyn Storage>>,
+    key: &str,
+    content_type: &str,
+    bytes: Vec<u8>,
+) -> Result<String, AppError<'a>> {
+    storage.put(key, content_type, bytes).await.map_err(|e| {
+        error!("Failed to store media '{}': {}", key, e);
+        AppError::default()
+    })
+}
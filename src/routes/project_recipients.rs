@@ -0,0 +1,65 @@
+use rocket::{http::Status, serde::json::Json};
+use std::borrow::Cow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    db::{project_recipients, Db},
+    errors::{AppError, CustomError},
+    models::project_recipient::{ProjectRecipient, ProjectRecipientCreate},
+    request_guards::bearer_auth::BearerAuth,
+};
+
+#[get("/project/<project_id>")]
+pub async fn get_project_recipients_by_project<'a>(
+    db: Db,
+    _auth: BearerAuth<'_>,
+    project_id: &str,
+) -> Result<Json<Vec<ProjectRecipient>>, AppError<'a>> {
+    let project_id_find = Cow::Owned(project_id.to_string());
+    let project_recipients = db
+        .run(move |conn| project_recipients::find_by_project(conn, &project_id_find))
+        .await?;
+    Ok(Json(project_recipients))
+}
+
+#[post("/", data = "<project_recipient>")]
+pub async fn create_project_recipient<'a>(
+    db: Db,
+    _auth: BearerAuth<'_>,
+    project_recipient: Json<ProjectRecipientCreate<'_>>,
+) -> Result<Json<ProjectRecipient>, AppError<'a>> {
+    project_recipient.validate()?;
+
+    let new_project_recipient = ProjectRecipient {
+        project_recipient_id: Uuid::new_v4().to_string(),
+        email: project_recipient.email.to_string(),
+        project_id: project_recipient.project_id.to_string(),
+    };
+    let project_recipient = db
+        .run(move |conn| project_recipients::create(conn, new_project_recipient))
+        .await?;
+    Ok(Json(project_recipient))
+}
+
+#[delete("/<id>")]
+pub async fn delete_project_recipient<'a>(
+    db: Db,
+    _auth: BearerAuth<'_>,
+    id: &str,
+) -> Result<Status, AppError<'a>> {
+    let id_find = Cow::Owned(id.to_string());
+    let id_delete = Cow::Owned(id.to_string());
+    if db
+        .run(move |conn| project_recipients::find_by_id(conn, &id_find))
+        .await?
+        .is_none()
+    {
+        return Err(CustomError::RecordDoesNotExist(id).into());
+    }
+
+    db.run(move |conn| project_recipients::delete(conn, &id_delete))
+        .await?;
+
+    Ok(Status::NoContent)
+}
@@ -1,37 +1,144 @@
-use rocket::{http::Status, serde::json::Json};
+use chrono::{DateTime, Duration, Utc};
+use rocket::{http::Status, serde::json::Json, State};
 use std::borrow::Cow;
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    db::{project_statuses, Db},
-    errors::{AppError, CustomError},
-    models::project_status::{ProjectStatus, ProjectStatusCreate, ProjectStatusUpdate},
+    db::{project_status_history, project_status_metrics, project_statuses, projects, uptime, Db},
+    errors::{AppError, CustomError, DbError},
+    models::{
+        health_state::HealthState,
+        project_status::{
+            ProjectStatus, ProjectStatusCreate, ProjectStatusFilter, ProjectStatusUpdate,
+        },
+        project_status_history::{ProjectStatusHistory, ReportSource},
+        project_status_metric::LatencyBucket,
+        sort::Sort,
+        uptime::StatusUptime,
+    },
+    pagination::{Page, PageRequest},
+    reports::{Measurement, Report, Reporter, Reports},
+    request_guards::bearer_auth::BearerAuth,
+    routes::projects::find_visible_project,
 };
 
-#[get("/")]
-pub async fn get_project_statuses<'a>(db: Db) -> Result<Json<Vec<ProjectStatus>>, AppError<'a>> {
-    let project_statuses = db.run(project_statuses::find).await?;
-    Ok(Json(project_statuses))
+/// Pages hold `limit` statuses; the next one is fetched by passing back `cursor`.
+/// Unauthenticated requests only get the statuses of published projects.
+#[get("/?<sort>&<limit>&<cursor>&<filter..>")]
+pub async fn get_project_statuses<'a>(
+    db: Db,
+    auth: Option<BearerAuth<'_>>,
+    sort: Option<Sort>,
+    limit: Option<u32>,
+    cursor: Option<&str>,
+    filter: ProjectStatusFilter,
+) -> Result<Page<ProjectStatus>, AppError<'a>> {
+    let sort = sort.unwrap_or_default();
+    let page = PageRequest::new(limit, cursor)?;
+    let published_only = auth.is_none();
+    let project_statuses = db
+        .run(move |conn| project_statuses::find(conn, &filter, sort, published_only, page))
+        .await?;
+    Ok(project_statuses)
 }
 
 #[get("/<id>")]
-pub async fn get_project_status<'a>(db: Db, id: &str) -> Result<Json<ProjectStatus>, AppError<'a>> {
+pub async fn get_project_status<'a>(
+    db: Db,
+    auth: Option<BearerAuth<'_>>,
+    id: &str,
+) -> Result<Json<ProjectStatus>, AppError<'a>> {
+    let project_status = find_visible_project_status(&db, auth.as_ref(), id).await?;
+    Ok(Json(project_status))
+}
+
+#[get("/<id>/history?<from>&<to>", rank = 2)]
+pub async fn get_project_status_history<'a>(
+    db: Db,
+    auth: Option<BearerAuth<'_>>,
+    id: &str,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<Json<Vec<ProjectStatusHistory>>, AppError<'a>> {
+    let from = from.map(parse_timestamp).transpose()?;
+    let to = to.map(parse_timestamp).transpose()?;
+
+    find_visible_project_status(&db, auth.as_ref(), id).await?;
+
+    let id_history = Cow::Owned(id.to_string());
+    let history = db
+        .run(move |conn| {
+            project_status_history::find_by_project_status(conn, &id_history, from, to)
+        })
+        .await?;
+    Ok(Json(history))
+}
+
+#[get("/<id>/uptime", rank = 2)]
+pub async fn get_project_status_uptime<'a>(
+    db: Db,
+    auth: Option<BearerAuth<'_>>,
+    id: &str,
+) -> Result<Json<StatusUptime>, AppError<'a>> {
+    find_visible_project_status(&db, auth.as_ref(), id).await?;
+
     let id_find = Cow::Owned(id.to_string());
-    let project_status = db
-        .run(move |conn| project_statuses::find_by_id(conn, &id_find))
+    let status_uptime = db
+        .run(move |conn| uptime::find_by_project_status(conn, &id_find))
         .await?;
-    match project_status {
-        Some(project_status) => Ok(Json(project_status)),
-        None => return Err(CustomError::RecordDoesNotExist(id).into()),
+    match status_uptime {
+        Some(status_uptime) => Ok(Json(status_uptime)),
+        None => Err(CustomError::RecordDoesNotExist(id).into()),
     }
 }
 
+/// The longest time range the latency percentiles are computed over in one request.
+const MAX_METRICS_RANGE_DAYS: i64 = 31;
+
+/// Latency percentiles per `bucket` seconds between `from` and `to`, the last day by
+/// default. The range can be at most `MAX_METRICS_RANGE_DAYS` long.
+#[get("/<id>/metrics?<from>&<to>&<bucket>", rank = 2)]
+pub async fn get_project_status_metrics<'a>(
+    db: Db,
+    auth: Option<BearerAuth<'_>>,
+    id: &str,
+    from: Option<&str>,
+    to: Option<&str>,
+    bucket: Option<u32>,
+) -> Result<Json<Vec<LatencyBucket>>, AppError<'a>> {
+    let to = to
+        .map(parse_timestamp)
+        .transpose()?
+        .unwrap_or_else(Utc::now);
+    let from = from
+        .map(parse_timestamp)
+        .transpose()?
+        .unwrap_or_else(|| to - Duration::days(1));
+    if to - from > Duration::days(MAX_METRICS_RANGE_DAYS) {
+        return Err(CustomError::RangeTooLong(MAX_METRICS_RANGE_DAYS).into());
+    }
+    let bucket = Duration::seconds(i64::from(bucket.unwrap_or(3600).max(60)));
+
+    find_visible_project_status(&db, auth.as_ref(), id).await?;
+
+    let id_metrics = Cow::Owned(id.to_string());
+    let metrics = db
+        .run(move |conn| {
+            project_status_metrics::find_by_project_status(conn, &id_metrics, from, to)
+        })
+        .await?;
+    Ok(Json(LatencyBucket::aggregate(&metrics, bucket)))
+}
+
 #[get("/project/<project_id>")]
 pub async fn get_project_statuses_by_project<'a>(
     db: Db,
+    auth: Option<BearerAuth<'_>>,
     project_id: &str,
 ) -> Result<Json<Vec<ProjectStatus>>, AppError<'a>> {
+    find_visible_project(&db, auth.as_ref(), project_id).await?;
+
     let project_id_find = Cow::Owned(project_id.to_string());
     let project_statuses = db
         .run(move |conn| project_statuses::find_by_project(conn, &project_id_find))
@@ -46,6 +153,7 @@ pub async fn create_project_status<'a>(
 ) -> Result<Json<ProjectStatus>, AppError<'a>> {
     project_status.validate()?;
 
+    find_live_project(&db, project_status.project_id).await?;
     if check_project_status_by_project(&db, project_status.name, project_status.project_id).await? {
         return Err(CustomError::ProjectStatusAlreadyExists(
             project_status.name,
@@ -59,7 +167,28 @@ pub async fn create_project_status<'a>(
         name: project_status.name.to_string(),
         is_healthy: false,
         project_id: project_status.project_id.to_string(),
+        check_enabled: project_status.check_enabled.unwrap_or_default(),
+        check_target: project_status.check_target.map(|t| t.to_string()),
+        check_expected_status: project_status.check_expected_status,
+        check_expected_body: project_status.check_expected_body.map(|b| b.to_string()),
+        check_max_latency_ms: project_status.check_max_latency_ms,
+        check_kind: project_status.check_kind.unwrap_or_default(),
+        check_interval_secs: project_status.check_interval_secs,
+        check_timeout_ms: project_status.check_timeout_ms,
+        check_grpc_service: project_status.check_grpc_service.map(|s| s.to_string()),
+        failure_threshold: project_status.failure_threshold.unwrap_or(1),
+        success_threshold: project_status.success_threshold.unwrap_or(1),
+        pending_reports: 0,
+        flapping: false,
+        flap_transitions: 0,
+        flap_window_started_at: Utc::now(),
+        health_state: HealthState::Unknown,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        deleted_at: None,
     };
+    new_project_status.validate_check()?;
+
     let project_status = db
         .run(move |conn| project_statuses::create(conn, new_project_status))
         .await?;
@@ -69,13 +198,14 @@ pub async fn create_project_status<'a>(
 #[put("/<id>", data = "<project_status>")]
 pub async fn update_project_status<'a>(
     db: Db,
+    reports: &State<Reports>,
+    auth: Option<BearerAuth<'_>>,
     id: &str,
     project_status: Json<ProjectStatusUpdate<'_>>,
 ) -> Result<Json<ProjectStatus>, AppError<'a>> {
     project_status.validate()?;
 
     let id_find = Cow::Owned(id.to_string());
-    let id_update = Cow::Owned(id.to_string());
     let existing_project_status = match db
         .run(move |conn| project_statuses::find_by_id(conn, &id_find))
         .await?
@@ -101,13 +231,9 @@ pub async fn update_project_status<'a>(
         None => existing_project_status.name,
     };
 
-    let updated_is_healthy = match project_status.is_healthy {
-        Some(new_is_healthy) => new_is_healthy,
-        None => existing_project_status.is_healthy,
-    };
-
     let updated_project_id = match project_status.project_id {
         Some(new_project_id) => {
+            find_live_project(&db, new_project_id).await?;
             if check_project_status_by_project(&db, &updated_name, new_project_id).await? {
                 return Err(
                     CustomError::ProjectStatusAlreadyExists(&updated_name, new_project_id).into(),
@@ -122,11 +248,73 @@ pub async fn update_project_status<'a>(
     let updated_project_status = ProjectStatus {
         project_status_id: existing_project_status.project_status_id,
         name: updated_name,
-        is_healthy: updated_is_healthy,
+        is_healthy: existing_project_status.is_healthy,
         project_id: updated_project_id,
+        check_enabled: project_status
+            .check_enabled
+            .unwrap_or(existing_project_status.check_enabled),
+        check_target: match project_status.check_target {
+            Some(new_check_target) => new_check_target.map(str::to_string),
+            None => existing_project_status.check_target,
+        },
+        check_expected_status: project_status
+            .check_expected_status
+            .unwrap_or(existing_project_status.check_expected_status),
+        check_expected_body: match project_status.check_expected_body {
+            Some(new_check_expected_body) => new_check_expected_body.map(str::to_string),
+            None => existing_project_status.check_expected_body,
+        },
+        check_max_latency_ms: project_status
+            .check_max_latency_ms
+            .unwrap_or(existing_project_status.check_max_latency_ms),
+        check_kind: project_status
+            .check_kind
+            .unwrap_or(existing_project_status.check_kind),
+        check_interval_secs: project_status
+            .check_interval_secs
+            .unwrap_or(existing_project_status.check_interval_secs),
+        check_timeout_ms: project_status
+            .check_timeout_ms
+            .unwrap_or(existing_project_status.check_timeout_ms),
+        check_grpc_service: match project_status.check_grpc_service {
+            Some(new_check_grpc_service) => new_check_grpc_service.map(str::to_string),
+            None => existing_project_status.check_grpc_service,
+        },
+        failure_threshold: project_status
+            .failure_threshold
+            .unwrap_or(existing_project_status.failure_threshold),
+        success_threshold: project_status
+            .success_threshold
+            .unwrap_or(existing_project_status.success_threshold),
+        pending_reports: existing_project_status.pending_reports,
+        flapping: existing_project_status.flapping,
+        flap_transitions: existing_project_status.flap_transitions,
+        flap_window_started_at: existing_project_status.flap_window_started_at,
+        health_state: existing_project_status.health_state,
+        created_at: existing_project_status.created_at,
+        updated_at: existing_project_status.updated_at,
+        deleted_at: existing_project_status.deleted_at,
+    };
+    updated_project_status.validate_check()?;
+    let reported_health_state = project_status
+        .health_state
+        .or_else(|| project_status.is_healthy.map(HealthState::from))
+        .map(Report::Health);
+
+    let reporter = Reporter::new(ReportSource::Rest, auth.map(|auth| auth.claims.sub));
+    let measurement = Measurement {
+        latency_ms: project_status.latency_ms,
+        detail: project_status.detail.map(|d| d.to_string()),
     };
-    let updated_project_status = db
-        .run(move |conn| project_statuses::update(conn, &id_update, updated_project_status))
+    let updated_project_status = reports
+        .save(
+            &db,
+            id,
+            Some(updated_project_status),
+            reported_health_state,
+            reporter,
+            measurement,
+        )
         .await?;
     Ok(Json(updated_project_status))
 }
@@ -149,6 +337,81 @@ pub async fn delete_project_status<'a>(db: Db, id: &str) -> Result<Status, AppEr
     Ok(Status::NoContent)
 }
 
+/// Takes the status out of the trash. Its project must not be deleted, and no live
+/// status of the project may have taken its name in the meantime.
+#[post("/<id>/restore", rank = 2)]
+pub async fn restore_project_status<'a>(
+    db: Db,
+    id: &str,
+) -> Result<Json<ProjectStatus>, AppError<'a>> {
+    let id_find = Cow::Owned(id.to_string());
+    let id_restore = Cow::Owned(id.to_string());
+    let deleted_project_status = match db
+        .run(move |conn| project_statuses::find_deleted_by_id(conn, &id_find))
+        .await?
+    {
+        Some(project_status) => project_status,
+        None => return Err(CustomError::RecordDoesNotExist(id).into()),
+    };
+
+    find_live_project(&db, &deleted_project_status.project_id).await?;
+    if check_project_status_by_project(
+        &db,
+        &deleted_project_status.name,
+        &deleted_project_status.project_id,
+    )
+    .await?
+    {
+        return Err(CustomError::ProjectStatusAlreadyExists(
+            &deleted_project_status.name,
+            &deleted_project_status.project_id,
+        )
+        .into());
+    }
+
+    let project_status = db
+        .run(move |conn| project_statuses::restore(conn, &id_restore))
+        .await?;
+    Ok(Json(project_status))
+}
+
+/// The status `id`, unless it doesn't exist or the request can't see its project.
+async fn find_visible_project_status<'a>(
+    db: &Db,
+    auth: Option<&BearerAuth<'_>>,
+    id: &str,
+) -> Result<ProjectStatus, AppError<'a>> {
+    let id_find = Cow::Owned(id.to_string());
+    let authenticated = auth.is_some();
+    let project_status = db
+        .run(move |conn| {
+            let project_status = match project_statuses::find_by_id(conn, &id_find)? {
+                Some(project_status) => project_status,
+                None => return Ok(None),
+            };
+            let visible = projects::is_visible(conn, &project_status.project_id, authenticated)?;
+            Ok::<_, DbError<'static>>(visible.then_some(project_status))
+        })
+        .await?;
+    match project_status {
+        Some(project_status) => Ok(project_status),
+        None => Err(CustomError::RecordDoesNotExist(id).into()),
+    }
+}
+
+/// Statuses can only be added to, moved to or restored under projects that aren't in
+/// the trash.
+async fn find_live_project<'a>(db: &Db, project_id: &str) -> Result<(), AppError<'a>> {
+    let project_id_find = Cow::Owned(project_id.to_string());
+    match db
+        .run(move |conn| projects::find_by_id(conn, &project_id_find))
+        .await?
+    {
+        Some(_) => Ok(()),
+        None => Err(CustomError::RecordDoesNotExist(project_id).into()),
+    }
+}
+
 async fn check_project_status_by_project<'a>(
     db: &Db,
     name: &str,
@@ -160,3 +423,9 @@ async fn check_project_status_by_project<'a>(
         .await?;
     Ok(existing_project_status.into_iter().any(|p| p.name == name))
 }
+
+fn parse_timestamp<'a>(value: &str) -> Result<DateTime<Utc>, AppError<'a>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|_| CustomError::InvalidTimestamp(value).into())
+}
@@ -1,29 +1,78 @@
-use rocket::{http::Status, serde::json::Json};
-use std::borrow::Cow;
+use chrono::Utc;
+use rocket::{http::Status, response::Redirect, serde::json::Json};
+use std::{borrow::Cow, collections::HashSet};
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    db::{projects, Db},
-    errors::{AppError, CustomError},
-    models::project::{Project, ProjectCreate, ProjectUpdate},
+    db::{projects, uptime, Db},
+    errors::{AppError, CustomError, DbError},
+    markdown,
+    models::{
+        project::{
+            Project, ProjectCreate, ProjectDetail, ProjectFilter, ProjectReorder,
+            ProjectSearchResult, ProjectUpdate,
+        },
+        sort::Sort,
+        uptime::ProjectUptime,
+        visibility::Visibility,
+    },
+    pagination::{Page, PageRequest},
+    request_guards::bearer_auth::BearerAuth,
 };
 
-#[get("/")]
-pub async fn get_projects<'a>(db: Db) -> Result<Json<Vec<Project>>, AppError<'a>> {
-    let projects = db.run(projects::find).await?;
-    Ok(Json(projects))
+#[derive(Responder)]
+pub enum ProjectBySlug {
+    Found(Json<ProjectDetail>),
+    Moved(Redirect),
+}
+
+/// `health` adds the project's rolled up health, `statuses` embeds its statuses.
+/// Without `sort`, projects come in the order set by reordering them.
+/// Pages hold `limit` projects; the next one is fetched by passing back `cursor`.
+/// Unauthenticated requests only get published projects.
+#[get("/?<health>&<statuses>&<sort>&<limit>&<cursor>&<filter..>")]
+pub async fn get_projects<'a>(
+    db: Db,
+    auth: Option<BearerAuth<'_>>,
+    health: Option<bool>,
+    statuses: Option<bool>,
+    sort: Option<Sort>,
+    limit: Option<u32>,
+    cursor: Option<&str>,
+    mut filter: ProjectFilter,
+) -> Result<Page<ProjectDetail>, AppError<'a>> {
+    if auth.is_none() {
+        filter.visibility = Some(Visibility::Published);
+    }
+    let (with_health, with_statuses) = (health.unwrap_or(false), statuses.unwrap_or(false));
+    let page = PageRequest::new(limit, cursor)?;
+    let projects = db
+        .run(move |conn| {
+            projects::find_details(conn, &filter, sort, page, with_health, with_statuses)
+        })
+        .await?;
+    Ok(projects)
 }
 
-#[get("/<id>")]
-pub async fn get_project<'a>(db: Db, id: &str) -> Result<Json<Project>, AppError<'a>> {
+#[get("/<id>?<health>&<statuses>")]
+pub async fn get_project<'a>(
+    db: Db,
+    auth: Option<BearerAuth<'_>>,
+    id: &str,
+    health: Option<bool>,
+    statuses: Option<bool>,
+) -> Result<Json<ProjectDetail>, AppError<'a>> {
+    let (with_health, with_statuses) = (health.unwrap_or(false), statuses.unwrap_or(false));
     let id_find = Cow::Owned(id.to_string());
     let project = db
-        .run(move |conn| projects::find_by_id(conn, &id_find))
+        .run(move |conn| projects::find_detail(conn, &id_find, with_health, with_statuses))
         .await?;
     match project {
-        Some(project) => Ok(Json(project)),
-        None => {
+        Some(project) if auth.is_some() || project.project.visibility.is_public() => {
+            Ok(Json(project))
+        }
+        _ => {
             return Err(AppError::new(
                 Status::NotFound,
                 Cow::from(CustomError::<'_>::RecordDoesNotExist(id).to_string()),
@@ -32,15 +81,93 @@ pub async fn get_project<'a>(db: Db, id: &str) -> Result<Json<Project>, AppError
     }
 }
 
+/// Full-text search over names, descriptions and repositories, accepting web search
+/// syntax such as quoted phrases, `or` and `-excluded` terms. Unauthenticated requests
+/// only find published projects.
+#[get("/search?<q>&<limit>&<cursor>")]
+pub async fn search_projects<'a>(
+    db: Db,
+    auth: Option<BearerAuth<'_>>,
+    q: &str,
+    limit: Option<u32>,
+    cursor: Option<&str>,
+) -> Result<Page<ProjectSearchResult>, AppError<'a>> {
+    let page = PageRequest::new(limit, cursor)?;
+    let terms = q.to_string();
+    let published_only = auth.is_none();
+    let results = db
+        .run(move |conn| projects::search(conn, &terms, published_only, page))
+        .await?;
+    Ok(results)
+}
+
+/// Looks a project up by slug, redirecting permanently from the slugs it had before
+/// being renamed.
+#[get("/slug/<slug>?<health>&<statuses>")]
+pub async fn get_project_by_slug<'a>(
+    db: Db,
+    auth: Option<BearerAuth<'_>>,
+    slug: &str,
+    health: Option<bool>,
+    statuses: Option<bool>,
+) -> Result<ProjectBySlug, AppError<'a>> {
+    let (with_health, with_statuses) = (health.unwrap_or(false), statuses.unwrap_or(false));
+    let slug_find = Cow::Owned(slug.to_string());
+    let (project, current_slug) = db
+        .run(move |conn| {
+            let project =
+                projects::find_detail_by_slug(conn, &slug_find, with_health, with_statuses)?;
+            let current_slug = match project {
+                Some(_) => None,
+                None => projects::find_slug_redirect(conn, &slug_find)?,
+            };
+            Ok::<_, DbError<'static>>((project, current_slug))
+        })
+        .await?;
+    match (project, current_slug) {
+        (Some(project), _) if auth.is_some() || project.project.visibility.is_public() => {
+            Ok(ProjectBySlug::Found(Json(project)))
+        }
+        (Some(_), _) => Err(CustomError::RecordDoesNotExist(slug).into()),
+        (None, Some(current_slug)) => Ok(ProjectBySlug::Moved(Redirect::moved(uri!(
+            "/projects",
+            get_project_by_slug(current_slug, health, statuses)
+        )))),
+        (None, None) => Err(CustomError::RecordDoesNotExist(slug).into()),
+    }
+}
+
 #[get("/name/<name>")]
-pub async fn get_project_by_name<'a>(db: Db, name: &str) -> Result<Json<Project>, AppError<'a>> {
+pub async fn get_project_by_name<'a>(
+    db: Db,
+    auth: Option<BearerAuth<'_>>,
+    name: &str,
+) -> Result<Json<Project>, AppError<'a>> {
     let name_find = Cow::Owned(name.to_string());
     let project = db
         .run(move |conn| projects::find_by_name(conn, &name_find))
         .await?;
     match project {
-        Some(project) => Ok(Json(project)),
-        None => Err(CustomError::RecordDoesNotExist(name).into()),
+        Some(project) if auth.is_some() || project.visibility.is_public() => Ok(Json(project)),
+        _ => Err(CustomError::RecordDoesNotExist(name).into()),
+    }
+}
+
+#[get("/<id>/uptime", rank = 2)]
+pub async fn get_project_uptime<'a>(
+    db: Db,
+    auth: Option<BearerAuth<'_>>,
+    id: &str,
+) -> Result<Json<ProjectUptime>, AppError<'a>> {
+    find_visible_project(&db, auth.as_ref(), id).await?;
+
+    let id_find = Cow::Owned(id.to_string());
+    let project_uptime = db
+        .run(move |conn| uptime::find_by_project(conn, &id_find))
+        .await?;
+    match project_uptime {
+        Some(project_uptime) => Ok(Json(project_uptime)),
+        None => Err(CustomError::RecordDoesNotExist(id).into()),
     }
 }
 
@@ -48,18 +175,29 @@ pub async fn get_project_by_name<'a>(db: Db, name: &str) -> Result<Json<Project>
 pub async fn create_project<'a>(
     db: Db,
     project: Json<ProjectCreate<'_>>,
-) -> Result<Json<Project>, AppError<'a>> {
+) -> Result<Json<ProjectDetail>, AppError<'a>> {
     project.validate()?;
 
+    let description = project.description.unwrap_or_default();
     let new_project = Project {
         project_id: Uuid::new_v4().to_string(),
         name: project.name.to_string(),
-        description: Some(project.description.unwrap_or_default().to_string()),
+        description: Some(description.to_string()),
         url: project.url.to_string(),
         github_repository: project.github_repository.to_string(),
+        rollup_policy: project.rollup_policy.unwrap_or_default(),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        slug: Project::slugify(project.name),
+        description_html: Some(markdown::render(description)),
+        featured: project.featured.unwrap_or_default(),
+        position: 0,
+        visibility: project.visibility.unwrap_or_default(),
+        deleted_at: None,
     };
+    let tag_names = project.tags.clone().unwrap_or_default();
     let project = db
-        .run(move |conn| projects::create(conn, new_project))
+        .run(move |conn| projects::create(conn, new_project, &tag_names))
         .await?;
     Ok(Json(project))
 }
@@ -69,7 +207,7 @@ pub async fn update_project<'a>(
     db: Db,
     id: &str,
     project: Json<ProjectUpdate<'_>>,
-) -> Result<Json<Project>, AppError<'a>> {
+) -> Result<Json<ProjectDetail>, AppError<'a>> {
     project.validate()?;
 
     let id_find = Cow::Owned(id.to_string());
@@ -100,13 +238,62 @@ pub async fn update_project<'a>(
             Some(new_github_repository) => new_github_repository.to_string(),
             None => existing_project.github_repository,
         },
+        rollup_policy: project
+            .rollup_policy
+            .unwrap_or(existing_project.rollup_policy),
+        created_at: existing_project.created_at,
+        updated_at: existing_project.updated_at,
+        slug: match project.name {
+            Some(new_name) => Project::slugify(new_name),
+            None => existing_project.slug,
+        },
+        description_html: match project.description {
+            Some(new_description) => Some(markdown::render(new_description)),
+            None => existing_project.description_html,
+        },
+        featured: project.featured.unwrap_or(existing_project.featured),
+        position: existing_project.position,
+        visibility: project.visibility.unwrap_or(existing_project.visibility),
+        deleted_at: existing_project.deleted_at,
     };
+    let tag_names = project.tags.clone();
     let updated_project = db
-        .run(move |conn| projects::update(conn, &id_update, updated_project))
+        .run(move |conn| projects::update(conn, &id_update, updated_project, tag_names.as_deref()))
         .await?;
     Ok(Json(updated_project))
 }
 
+/// Puts the listed projects first, in the given order, followed by the others in
+/// their current order.
+#[post("/reorder", data = "<order>")]
+pub async fn reorder_projects<'a>(
+    db: Db,
+    order: Json<ProjectReorder>,
+) -> Result<Status, AppError<'a>> {
+    order.validate()?;
+
+    let mut ids = order.into_inner().project_ids;
+    let mut seen = HashSet::new();
+    ids.retain(|id| seen.insert(id.clone()));
+
+    let ids_find = ids.clone();
+    let existing = db
+        .run(move |conn| projects::find_by_ids(conn, &ids_find))
+        .await?;
+    if let Some(missing) = ids
+        .iter()
+        .find(|id| !existing.iter().any(|p| &p.project_id == *id))
+    {
+        return Err(CustomError::RecordDoesNotExist(missing).into());
+    }
+
+    db.run(move |conn| projects::reorder(conn, &ids)).await?;
+
+    Ok(Status::NoContent)
+}
+
+/// Moves the project and its statuses to the trash. Its media files are kept until
+/// the project is purged.
 #[delete("/<id>")]
 pub async fn delete_project<'a>(db: Db, id: &str) -> Result<Status, AppError<'a>> {
     let id_find = Cow::Owned(id.to_string());
@@ -124,3 +311,63 @@ pub async fn delete_project<'a>(db: Db, id: &str) -> Result<Status, AppError<'a>
 
     Ok(Status::NoContent)
 }
+
+/// Takes the project out of the trash along with the statuses deleted with it, unless
+/// another project has taken its name since.
+#[post("/<id>/restore?<health>&<statuses>", rank = 2)]
+pub async fn restore_project<'a>(
+    db: Db,
+    id: &str,
+    health: Option<bool>,
+    statuses: Option<bool>,
+) -> Result<Json<ProjectDetail>, AppError<'a>> {
+    let (with_health, with_statuses) = (health.unwrap_or(false), statuses.unwrap_or(false));
+    let id_find = Cow::Owned(id.to_string());
+    let id_restore = Cow::Owned(id.to_string());
+    let deleted_project = match db
+        .run(move |conn| projects::find_deleted_by_id(conn, &id_find))
+        .await?
+    {
+        Some(project) => project,
+        None => return Err(CustomError::RecordDoesNotExist(id).into()),
+    };
+
+    let name_find = Cow::Owned(deleted_project.name.clone());
+    if db
+        .run(move |conn| projects::find_by_name(conn, &name_find))
+        .await?
+        .is_some()
+    {
+        return Err(CustomError::RecordAlreadyExists(&deleted_project.name).into());
+    }
+
+    let project = db
+        .run(move |conn| match projects::restore(conn, &id_restore)? {
+            Some(project) => {
+                projects::find_detail(conn, &project.project_id, with_health, with_statuses)
+            }
+            None => Ok(None),
+        })
+        .await?;
+    match project {
+        Some(project) => Ok(Json(project)),
+        None => Err(CustomError::RecordDoesNotExist(id).into()),
+    }
+}
+
+/// The project `id`, unless it doesn't exist or is a draft or archived and the request
+/// is unauthenticated.
+pub async fn find_visible_project<'a>(
+    db: &Db,
+    auth: Option<&BearerAuth<'_>>,
+    id: &str,
+) -> Result<Project, AppError<'a>> {
+    let id_find = Cow::Owned(id.to_string());
+    match db
+        .run(move |conn| projects::find_by_id(conn, &id_find))
+        .await?
+    {
+        Some(project) if auth.is_some() || project.visibility.is_public() => Ok(project),
+        _ => Err(CustomError::RecordDoesNotExist(id).into()),
+    }
+}
@@ -0,0 +1,33 @@
+use chrono::Utc;
+use rocket::response::content::RawHtml;
+use std::borrow::Cow;
+
+use crate::{
+    db::{status_page, Db},
+    errors::{AppError, CustomError},
+    status_html::render,
+};
+
+#[get("/")]
+pub async fn get_status_page<'a>(db: Db) -> Result<RawHtml<String>, AppError<'a>> {
+    let projects = db.run(status_page::find).await?;
+    Ok(RawHtml(render("Status", &projects, Utc::now())))
+}
+
+#[get("/<name>")]
+pub async fn get_project_status_page<'a>(
+    db: Db,
+    name: &str,
+) -> Result<RawHtml<String>, AppError<'a>> {
+    let name_find = Cow::Owned(name.to_string());
+    let project = db
+        .run(move |conn| status_page::find_by_project_name(conn, &name_find))
+        .await?;
+    match project {
+        Some(project) => {
+            let title = format!("{} status", project.project.name);
+            Ok(RawHtml(render(&title, &[project], Utc::now())))
+        }
+        None => Err(CustomError::RecordDoesNotExist(name).into()),
+    }
+}
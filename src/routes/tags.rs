@@ -0,0 +1,83 @@
+use rocket::{http::Status, serde::json::Json};
+use std::borrow::Cow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    db::{tags, Db},
+    errors::{AppError, CustomError},
+    models::tag::{Tag, TagCreate, TagUpdate},
+};
+
+#[get("/")]
+pub async fn get_tags<'a>(db: Db) -> Result<Json<Vec<Tag>>, AppError<'a>> {
+    let tags = db.run(tags::find).await?;
+    Ok(Json(tags))
+}
+
+#[get("/<id>")]
+pub async fn get_tag<'a>(db: Db, id: &str) -> Result<Json<Tag>, AppError<'a>> {
+    let id_find = Cow::Owned(id.to_string());
+    let tag = db.run(move |conn| tags::find_by_id(conn, &id_find)).await?;
+    match tag {
+        Some(tag) => Ok(Json(tag)),
+        None => Err(CustomError::RecordDoesNotExist(id).into()),
+    }
+}
+
+#[post("/", data = "<tag>")]
+pub async fn create_tag<'a>(db: Db, tag: Json<TagCreate<'_>>) -> Result<Json<Tag>, AppError<'a>> {
+    tag.validate()?;
+
+    let new_tag = Tag {
+        tag_id: Uuid::new_v4().to_string(),
+        name: Tag::normalize(tag.name),
+    };
+    let tag = db.run(move |conn| tags::create(conn, new_tag)).await?;
+    Ok(Json(tag))
+}
+
+#[put("/<id>", data = "<tag>")]
+pub async fn update_tag<'a>(
+    db: Db,
+    id: &str,
+    tag: Json<TagUpdate<'_>>,
+) -> Result<Json<Tag>, AppError<'a>> {
+    tag.validate()?;
+
+    let id_find = Cow::Owned(id.to_string());
+    let id_update = Cow::Owned(id.to_string());
+    let existing_tag = match db.run(move |conn| tags::find_by_id(conn, &id_find)).await? {
+        Some(tag) => tag,
+        None => return Err(CustomError::RecordDoesNotExist(id).into()),
+    };
+
+    let updated_tag = Tag {
+        tag_id: existing_tag.tag_id,
+        name: match tag.name {
+            Some(new_name) => Tag::normalize(new_name),
+            None => existing_tag.name,
+        },
+    };
+    let updated_tag = db
+        .run(move |conn| tags::update(conn, &id_update, updated_tag))
+        .await?;
+    Ok(Json(updated_tag))
+}
+
+#[delete("/<id>")]
+pub async fn delete_tag<'a>(db: Db, id: &str) -> Result<Status, AppError<'a>> {
+    let id_find = Cow::Owned(id.to_string());
+    let id_delete = Cow::Owned(id.to_string());
+    if db
+        .run(move |conn| tags::find_by_id(conn, &id_find))
+        .await?
+        .is_none()
+    {
+        return Err(CustomError::RecordDoesNotExist(id).into());
+    }
+
+    db.run(move |conn| tags::delete(conn, &id_delete)).await?;
+
+    Ok(Status::NoContent)
+}
@@ -0,0 +1,25 @@
+use rocket::serde::json::Json;
+
+use crate::{
+    db::{project_statuses, projects, Db},
+    errors::{AppError, DbError},
+    models::trash::Trash,
+    request_guards::bearer_auth::BearerAuth,
+};
+
+/// Everything in the trash, most recently deleted first. Only available to
+/// authenticated requests.
+#[get("/")]
+pub async fn get_trash<'a>(db: Db, _auth: BearerAuth<'_>) -> Result<Json<Trash>, AppError<'a>> {
+    let trash = db
+        .run(move |conn| {
+            let projects = projects::find_deleted(conn)?;
+            let project_statuses = project_statuses::find_deleted(conn)?;
+            Ok::<_, DbError<'static>>(Trash {
+                projects,
+                project_statuses,
+            })
+        })
+        .await?;
+    Ok(Json(trash))
+}
@@ -1,24 +1,275 @@
 // @generated automatically by Diesel CLI.
 
+pub mod sql_types {
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "check_kind"))]
+    pub struct CheckKind;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "health_state"))]
+    pub struct HealthState;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "incident_severity"))]
+    pub struct IncidentSeverity;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "incident_state"))]
+    pub struct IncidentState;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "link_kind"))]
+    pub struct LinkKind;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "media_kind"))]
+    pub struct MediaKind;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "report_source"))]
+    pub struct ReportSource;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "rollup_policy"))]
+    pub struct RollupPolicy;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "visibility"))]
+    pub struct Visibility;
+}
+
+diesel::table! {
+    incident_project_statuses (incident_id, project_status_id) {
+        incident_id -> Varchar,
+        project_status_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::IncidentState;
+
+    incident_timeline (incident_timeline_id) {
+        incident_timeline_id -> Varchar,
+        incident_id -> Varchar,
+        state -> IncidentState,
+        message -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::IncidentSeverity;
+    use super::sql_types::IncidentState;
+
+    incidents (incident_id) {
+        incident_id -> Varchar,
+        project_id -> Varchar,
+        title -> Varchar,
+        severity -> IncidentSeverity,
+        state -> IncidentState,
+        automatic -> Bool,
+        created_at -> Timestamptz,
+        resolved_at -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
+    maintenance_window_project_statuses (maintenance_window_id, project_status_id) {
+        maintenance_window_id -> Varchar,
+        project_status_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    maintenance_windows (maintenance_window_id) {
+        maintenance_window_id -> Varchar,
+        project_id -> Varchar,
+        message -> Varchar,
+        starts_at -> Timestamptz,
+        ends_at -> Timestamptz,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::LinkKind;
+
+    project_links (project_link_id) {
+        project_link_id -> Varchar,
+        project_id -> Varchar,
+        kind -> LinkKind,
+        url -> Varchar,
+        label -> Nullable<Varchar>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::MediaKind;
+
+    project_media (project_media_id) {
+        project_media_id -> Varchar,
+        project_id -> Varchar,
+        kind -> MediaKind,
+        content_type -> Varchar,
+        byte_size -> Int4,
+        width -> Int4,
+        height -> Int4,
+        caption -> Nullable<Varchar>,
+        url -> Varchar,
+        thumbnail_url -> Varchar,
+        storage_key -> Varchar,
+        thumbnail_key -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    project_recipients (project_recipient_id) {
+        project_recipient_id -> Varchar,
+        email -> Varchar,
+        project_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    project_slug_redirects (slug) {
+        slug -> Varchar,
+        project_id -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::HealthState;
+    use super::sql_types::ReportSource;
+
+    project_status_history (project_status_history_id) {
+        project_status_history_id -> Varchar,
+        project_status_id -> Varchar,
+        old_state -> HealthState,
+        new_state -> HealthState,
+        source -> ReportSource,
+        reporter -> Nullable<Varchar>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    project_status_metrics (project_status_metric_id) {
+        project_status_metric_id -> Varchar,
+        project_status_id -> Varchar,
+        latency_ms -> Nullable<Int4>,
+        detail -> Nullable<Varchar>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::CheckKind;
+    use super::sql_types::HealthState;
+
     project_statuses (project_status_id) {
         project_status_id -> Varchar,
         name -> Varchar,
         is_healthy -> Bool,
         project_id -> Varchar,
+        check_enabled -> Bool,
+        check_target -> Nullable<Varchar>,
+        check_expected_status -> Nullable<Int4>,
+        check_expected_body -> Nullable<Varchar>,
+        check_max_latency_ms -> Nullable<Int4>,
+        check_kind -> CheckKind,
+        check_interval_secs -> Nullable<Int4>,
+        check_timeout_ms -> Nullable<Int4>,
+        check_grpc_service -> Nullable<Varchar>,
+        failure_threshold -> Int4,
+        success_threshold -> Int4,
+        pending_reports -> Int4,
+        flapping -> Bool,
+        flap_transitions -> Int4,
+        flap_window_started_at -> Timestamptz,
+        health_state -> HealthState,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        deleted_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    project_tags (project_id, tag_id) {
+        project_id -> Varchar,
+        tag_id -> Varchar,
     }
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::RollupPolicy;
+    use super::sql_types::Visibility;
+
     projects (project_id) {
         project_id -> Varchar,
         name -> Varchar,
         description -> Nullable<Varchar>,
         url -> Varchar,
         github_repository -> Varchar,
+        rollup_policy -> RollupPolicy,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        slug -> Varchar,
+        description_html -> Nullable<Varchar>,
+        featured -> Bool,
+        position -> Int4,
+        visibility -> Visibility,
+        deleted_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    tags (tag_id) {
+        tag_id -> Varchar,
+        name -> Varchar,
     }
 }
 
+diesel::joinable!(incident_project_statuses -> incidents (incident_id));
+diesel::joinable!(incident_project_statuses -> project_statuses (project_status_id));
+diesel::joinable!(incident_timeline -> incidents (incident_id));
+diesel::joinable!(incidents -> projects (project_id));
+diesel::joinable!(maintenance_window_project_statuses -> maintenance_windows (maintenance_window_id));
+diesel::joinable!(maintenance_window_project_statuses -> project_statuses (project_status_id));
+diesel::joinable!(maintenance_windows -> projects (project_id));
+diesel::joinable!(project_links -> projects (project_id));
+diesel::joinable!(project_media -> projects (project_id));
+diesel::joinable!(project_recipients -> projects (project_id));
+diesel::joinable!(project_slug_redirects -> projects (project_id));
+diesel::joinable!(project_status_history -> project_statuses (project_status_id));
+diesel::joinable!(project_status_metrics -> project_statuses (project_status_id));
 diesel::joinable!(project_statuses -> projects (project_id));
+diesel::joinable!(project_tags -> projects (project_id));
+diesel::joinable!(project_tags -> tags (tag_id));
 
-diesel::allow_tables_to_appear_in_same_query!(project_statuses, projects);
+diesel::allow_tables_to_appear_in_same_query!(
+    incident_project_statuses,
+    incident_timeline,
+    incidents,
+    maintenance_window_project_statuses,
+    maintenance_windows,
+    project_links,
+    project_media,
+    project_recipients,
+    project_slug_redirects,
+    project_status_history,
+    project_status_metrics,
+    project_statuses,
+    project_tags,
+    projects,
+    tags,
+);
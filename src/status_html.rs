@@ -0,0 +1,174 @@
+use chrono::{DateTime, Duration, Utc};
+use rocket::http::RawStr;
+
+use crate::{
+    escape,
+    models::{
+        health_state::HealthState,
+        incident::IncidentSeverity,
+        rollup::ProjectHealth,
+        status_page::{StatusPageProject, StatusPageStatus},
+        uptime::MAX_WINDOW_DAYS,
+    },
+};
+
+const STYLE: &str = r#"
+body { font-family: system-ui, sans-serif; margin: 0 auto; max-width: 960px; padding: 1rem; color: #1f2328; }
+a { color: inherit; }
+.project { border: 1px solid #d0d7de; border-radius: 6px; margin-bottom: 2rem; }
+.project > header { display: flex; justify-content: space-between; align-items: center; padding: 1rem; color: #fff; }
+.project > header h2 { margin: 0; }
+.health-healthy { background: #1a7f37; }
+.health-degraded { background: #bf8700; }
+.health-down { background: #cf222e; }
+.health-unknown { background: #6e7781; }
+.status { padding: 0.75rem 1rem; border-top: 1px solid #d0d7de; }
+.status .name { display: flex; justify-content: space-between; }
+.status[data-state=operational] .state { color: #1a7f37; }
+.status[data-state=degraded] .state, .status[data-state=partial_outage] .state { color: #bf8700; }
+.status[data-state=major_outage] .state { color: #cf222e; }
+.status[data-state=maintenance] .state { color: #0969da; }
+.status[data-state=unknown] .state { color: #6e7781; }
+.bars { display: flex; gap: 1px; margin-top: 0.5rem; height: 2rem; }
+.bar { flex: 1; border-radius: 1px; background: #d0d7de; }
+.bar.up { background: #1a7f37; }
+.bar.partial { background: #bf8700; }
+.bar.down { background: #cf222e; }
+.incidents { padding: 0 1rem 1rem; }
+.incident { border-left: 4px solid #6e7781; padding-left: 0.75rem; margin-top: 1rem; }
+.incident.minor { border-color: #bf8700; }
+.incident.major, .incident.critical { border-color: #cf222e; }
+.incident .meta { color: #57606a; font-size: 0.875rem; }
+"#;
+
+/// Renders the status page of `projects`, which follows the `project_events`
+/// stream to keep statuses current and reloads when an incident changes.
+pub fn render(title: &str, projects: &[StatusPageProject], now: DateTime<Utc>) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+        escape::markup(title),
+        STYLE,
+        escape::markup(title)
+    );
+    if projects.is_empty() {
+        html.push_str("<p>No projects yet.</p>\n");
+    }
+    for project in projects {
+        render_project(&mut html, project, now);
+    }
+    html.push_str(&script());
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn render_project(html: &mut String, project: &StatusPageProject, now: DateTime<Utc>) {
+    let (class, label) = match project.health {
+        ProjectHealth::Healthy => ("healthy", "All systems operational"),
+        ProjectHealth::Degraded => ("degraded", "Degraded performance"),
+        ProjectHealth::Down => ("down", "Major outage"),
+        ProjectHealth::Unknown => ("unknown", "No data"),
+    };
+    html.push_str(&format!(
+        "<section class=\"project\">\n<header class=\"health-{}\"><h2><a href=\"/status/{}\">{}</a></h2><span>{}</span></header>\n",
+        class,
+        escape::markup(RawStr::new(&project.project.name).percent_encode().as_str()),
+        escape::markup(&project.project.name),
+        label
+    ));
+    for status in &project.project_statuses {
+        render_status(html, status, now);
+    }
+    if !project.incidents.is_empty() {
+        html.push_str("<div class=\"incidents\">\n<h3>Recent incidents</h3>\n");
+        for incident in &project.incidents {
+            let severity = match incident.severity {
+                IncidentSeverity::Minor => "minor",
+                IncidentSeverity::Major => "major",
+                IncidentSeverity::Critical => "critical",
+            };
+            html.push_str(&format!(
+                "<div class=\"incident {}\"><strong>{}</strong><div class=\"meta\">{}, {} &middot; opened {}</div></div>\n",
+                severity,
+                escape::markup(&incident.title),
+                severity,
+                incident.state.label(),
+                incident.created_at.format("%Y-%m-%d %H:%M UTC")
+            ));
+        }
+        html.push_str("</div>\n");
+    }
+    html.push_str("</section>\n");
+}
+
+fn render_status(html: &mut String, status: &StatusPageStatus, now: DateTime<Utc>) {
+    let project_status = &status.project_status;
+    html.push_str(&format!(
+        "<div class=\"status\" data-status-id=\"{}\" data-state=\"{}\">\n\
+         <div class=\"name\"><span>{}</span><span class=\"state\">{}</span></div>\n<div class=\"bars\">",
+        escape::markup(&project_status.project_status_id),
+        state_key(project_status.health_state),
+        escape::markup(&project_status.name),
+        project_status.health_state.label()
+    ));
+    for (i, uptime) in status.daily_uptime.iter().enumerate() {
+        let day = now - Duration::days(MAX_WINDOW_DAYS - 1 - i as i64);
+        let (class, text) = match uptime {
+            Some(uptime) if *uptime >= 99.0 => ("up", format!("{:.2}%", uptime)),
+            Some(uptime) if *uptime >= 95.0 => ("partial", format!("{:.2}%", uptime)),
+            Some(uptime) => ("down", format!("{:.2}%", uptime)),
+            None => ("", String::from("No data")),
+        };
+        html.push_str(&format!(
+            "<div class=\"bar {}\" title=\"{}: {}\"></div>",
+            class,
+            day.format("%Y-%m-%d"),
+            text
+        ));
+    }
+    html.push_str("</div>\n</div>\n");
+}
+
+fn script() -> String {
+    let labels = [
+        HealthState::Operational,
+        HealthState::Degraded,
+        HealthState::PartialOutage,
+        HealthState::MajorOutage,
+        HealthState::Maintenance,
+        HealthState::Unknown,
+    ]
+    .iter()
+    .map(|s| format!("{}: \"{}\"", state_key(*s), s.label()))
+    .collect::<Vec<String>>()
+    .join(", ");
+    format!(
+        r#"<script>
+const labels = {{ {} }};
+const events = new EventSource("/project_events");
+events.onmessage = (event) => {{
+    const status = JSON.parse(event.data);
+    const row = document.querySelector(`[data-status-id="${{status.project_status_id}}"]`);
+    if (!row) return;
+    row.dataset.state = status.health_state;
+    row.querySelector(".state").textContent = labels[status.health_state] || status.health_state;
+}};
+events.addEventListener("incident", () => window.location.reload());
+</script>
+"#,
+        labels
+    )
+}
+
+/// The serialized name of `health_state`.
+fn state_key(health_state: HealthState) -> &'static str {
+    match health_state {
+        HealthState::Operational => "operational",
+        HealthState::Degraded => "degraded",
+        HealthState::PartialOutage => "partial_outage",
+        HealthState::MajorOutage => "major_outage",
+        HealthState::Maintenance => "maintenance",
+        HealthState::Unknown => "unknown",
+    }
+}